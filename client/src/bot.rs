@@ -0,0 +1,136 @@
+use std::io::Write;
+
+use server::{protocol, Object, Space};
+
+use crate::{connect, read_state};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Play,
+    Spectate,
+}
+
+/// Tether input, which has the same effect however often it is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TetherAction {
+    /// Latch onto the closest planet or ship in range unless tethered already.
+    Latch,
+    Release,
+}
+
+/// Decision sent to the server for a single tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Action {
+    /// Thrust angle in radians, `None` to drift. Under rotational control
+    /// any angle thrusts forward.
+    pub direction: Option<f64>,
+    /// Thrust in reverse under rotational control.
+    pub reverse: bool,
+    /// Turn under rotational control, negative towards decreasing angles.
+    pub turn: f64,
+    /// Drop a mine where the ship is.
+    pub mine: bool,
+    /// Drop a gravity anchor where the ship is.
+    pub anchor: bool,
+    pub tether: Option<TetherAction>,
+    /// Switch between playing and spectating.
+    pub role: Option<Role>,
+}
+
+impl Action {
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut flags = 0;
+        if self.direction.is_some() {
+            flags |= protocol::MOVE;
+        }
+        if self.reverse {
+            flags |= protocol::REVERSE;
+        }
+        if self.turn < 0. {
+            flags |= protocol::TURN_LEFT;
+        } else if self.turn > 0. {
+            flags |= protocol::TURN_RIGHT;
+        }
+        if self.mine {
+            flags |= protocol::MINE;
+        }
+        if self.anchor {
+            flags |= protocol::ANCHOR;
+        }
+        match self.role {
+            Some(Role::Play) => flags |= protocol::PLAY,
+            Some(Role::Spectate) => flags |= protocol::SPECTATE,
+            None => {}
+        }
+        let mut msg = vec![flags];
+        msg.append(&mut f64::to_be_bytes(self.direction.unwrap_or(0.)).to_vec());
+        msg.push(match self.tether {
+            Some(TetherAction::Latch) => protocol::TETHER_LATCH,
+            Some(TetherAction::Release) => protocol::TETHER_RELEASE,
+            None => 0,
+        });
+        msg
+    }
+}
+
+/// Game state received from the server, seen from the bot's ship.
+pub struct Observation {
+    /// Own ship id, `None` while spectating.
+    pub id: Option<u8>,
+    pub tick: usize,
+    pub space: Space,
+}
+
+impl Observation {
+    pub fn ship(&self) -> Option<&Object> {
+        self.id.and_then(|id| self.space.ship(id))
+    }
+
+    pub fn other_ships(&self) -> impl Iterator<Item = (u8, &Object)> {
+        self.space
+            .ships()
+            .filter(move |(id, _)| Some(*id) != self.id)
+    }
+
+    pub fn planets(&self) -> impl Iterator<Item = &Object> {
+        self.space.planets()
+    }
+}
+
+pub trait Bot {
+    fn act(&mut self, observation: &Observation) -> Action;
+}
+
+impl<F: FnMut(&Observation) -> Action> Bot for F {
+    fn act(&mut self, observation: &Observation) -> Action {
+        self(observation)
+    }
+}
+
+/// Connects to the server and lets `bot` play until the connection closes
+/// or `max_ticks` states were received. Returns the last own ship id.
+pub fn run_bot(
+    addr: &str,
+    role: Role,
+    bot: &mut impl Bot,
+    max_ticks: Option<usize>,
+) -> Result<Option<u8>, Box<dyn std::error::Error>> {
+    let mut stream = connect(addr, role)?;
+
+    let mut id = None;
+    let mut tick = 0;
+    while Some(tick) != max_ticks {
+        let (ship_id, state) = read_state(&mut stream)?;
+        id = ship_id;
+        let observation = Observation {
+            id,
+            tick,
+            space: bincode::deserialize(&state)?,
+        };
+        let action = bot.act(&observation);
+        stream.write_all(&action.to_bytes())?;
+        tick += 1;
+    }
+
+    Ok(id)
+}
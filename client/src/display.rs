@@ -1,35 +1,142 @@
 use std::sync::mpsc::{Receiver, Sender};
+use std::time::{Duration, Instant};
 
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
-use sdl2::rect::Point;
+use sdl2::rect::{Point, Rect};
 use sdl2::render::Canvas;
 use sdl2::video::Window;
 
-use server::{DisplayInfo, DisplayType, Drawer, Space};
+use server::{ControlModel, DisplayInfo, DisplayType, Drawer, LineInfo, Space};
+
+use crate::bot::{Action, Role, TetherAction};
+use crate::replay::{Playback, FRAME_SECONDS};
 
 const WIDTH: u32 = 1000;
 const HEIGHT: u32 = 1000;
+const CAMERA_PAN: f64 = 0.05;
+const CAMERA_ZOOM: f64 = 1.25;
+const GAUGE_MARGIN: i32 = 10;
+const GAUGE_WIDTH: u32 = 200;
+const GAUGE_HEIGHT: u32 = 10;
+
+enum CameraMode {
+    Free,
+    Follow(u8),
+}
+
+/// Maps space coordinates to the window, `center` is shown in the middle.
+struct Camera {
+    mode: CameraMode,
+    center: (f64, f64),
+    zoom: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            mode: CameraMode::Free,
+            center: (0.5, 0.5),
+            zoom: 1.,
+        }
+    }
+}
+
+impl Camera {
+    fn pan(&mut self, dx: f64, dy: f64) {
+        self.mode = CameraMode::Free;
+        self.center.0 += dx / self.zoom;
+        self.center.1 += dy / self.zoom;
+    }
+
+    /// Follows own ship first, then cycles through the other ships.
+    fn follow_next(&mut self, space: &Space, own_id: Option<u8>) {
+        let mut ids: Vec<u8> = space.ships().map(|(id, _)| id).collect();
+        ids.sort();
+        let next = match self.mode {
+            CameraMode::Follow(current) => ids.iter().find(|&&id| id > current),
+            CameraMode::Free => ids.iter().find(|&&id| Some(id) == own_id),
+        };
+        if let Some(&id) = next.or(ids.first()) {
+            self.mode = CameraMode::Follow(id);
+        }
+    }
+
+    fn update(&mut self, space: &Space) {
+        if let CameraMode::Follow(id) = self.mode {
+            match space.ship(id) {
+                Some(ship) => self.center = (ship.location.x, ship.location.y),
+                None => self.mode = CameraMode::Free,
+            }
+        }
+    }
+
+    fn transform(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x - self.center.0) * self.zoom + 0.5,
+            (y - self.center.1) * self.zoom + 0.5,
+        )
+    }
+}
+
+pub enum Source {
+    Live {
+        state_recv: Receiver<(Option<u8>, Vec<u8>)>,
+        command_send: Sender<Vec<u8>>,
+    },
+    Replay(Box<Playback>),
+}
 
 struct CanvasDrawer {
     pub canvas: Canvas<Window>,
+    camera: Camera,
 }
 
-impl Drawer for CanvasDrawer {
-    fn draw(&mut self, display_info: DisplayInfo, width: u32, height: u32) -> Result<(), String> {
-        match display_info.display_type {
+impl CanvasDrawer {
+    fn set_color(&mut self, display_type: DisplayType) {
+        match display_type {
             DisplayType::Planet => self.canvas.set_draw_color(Color::RED),
+            DisplayType::BlackHole => self.canvas.set_draw_color(Color::MAGENTA),
+            DisplayType::Repulsor => self.canvas.set_draw_color(Color::CYAN),
+            DisplayType::Wormhole => self.canvas.set_draw_color(Color::GREEN),
             DisplayType::Ship => self.canvas.set_draw_color(Color::WHITE),
+            DisplayType::Wall => self.canvas.set_draw_color(Color::GRAY),
+            DisplayType::DeadlyWall => self.canvas.set_draw_color(Color::YELLOW),
+            DisplayType::Pickup => self.canvas.set_draw_color(Color::RGB(255, 165, 0)),
+            DisplayType::Mine => self.canvas.set_draw_color(Color::RGB(255, 105, 180)),
+            DisplayType::Anchor => self.canvas.set_draw_color(Color::BLUE),
+            DisplayType::Tether => self.canvas.set_draw_color(Color::RGB(135, 206, 250)),
         }
-        draw_circle(
-            &mut self.canvas,
-            Point::new(
-                (display_info.x * width as f64) as i32,
-                (display_info.y * height as f64) as i32,
-            ),
-            (display_info.radius * f64::from(width)) as i32,
-        )
+    }
+}
+
+impl Drawer for CanvasDrawer {
+    fn draw(&mut self, display_info: DisplayInfo, width: u32, height: u32) -> Result<(), String> {
+        self.set_color(display_info.display_type);
+        let (x, y) = self.camera.transform(display_info.x, display_info.y);
+        let center = Point::new((x * width as f64) as i32, (y * height as f64) as i32);
+        let radius = display_info.radius * self.camera.zoom * f64::from(width);
+        draw_circle(&mut self.canvas, center, radius as i32)?;
+        // nose pointing along the heading
+        if let Some(heading) = display_info.heading {
+            let nose = Point::new(
+                center.x() + (2. * radius * heading.cos()) as i32,
+                center.y() + (2. * radius * heading.sin()) as i32,
+            );
+            self.canvas.draw_line(center, nose)?;
+        }
+        Ok(())
+    }
+
+    fn draw_line(&mut self, line_info: LineInfo, width: u32, height: u32) -> Result<(), String> {
+        self.set_color(line_info.display_type);
+        let point = |(x, y): (f64, f64)| {
+            let (x, y) = self.camera.transform(x, y);
+            Point::new((x * width as f64) as i32, (y * height as f64) as i32)
+        };
+        let (from, to) = (point(line_info.from), point(line_info.to));
+        self.canvas.draw_line(from, to)
     }
 }
 
@@ -62,10 +169,39 @@ fn draw_circle(canvas: &mut Canvas<Window>, center: Point, radius: i32) -> Resul
     Ok(())
 }
 
-pub fn display_game(
-    state_recv: Receiver<Vec<u8>>,
-    command_send: Sender<Vec<u8>>,
-) -> Result<(), Box<dyn std::error::Error>> {
+/// Bar in the top left corner filled to `fill` between 0 and 1.
+fn draw_gauge(canvas: &mut Canvas<Window>, fill: f64) -> Result<(), String> {
+    let (width, height) = (GAUGE_WIDTH, GAUGE_HEIGHT);
+    canvas.set_draw_color(Color::GRAY);
+    canvas.draw_rect(Rect::new(GAUGE_MARGIN, GAUGE_MARGIN, width, height))?;
+    let filled = (fill.clamp(0., 1.) * f64::from(width)) as u32;
+    if filled > 0 {
+        canvas.set_draw_color(Color::GREEN);
+        canvas.fill_rect(Rect::new(GAUGE_MARGIN, GAUGE_MARGIN, filled, height))?;
+    }
+    Ok(())
+}
+
+fn control_playback(playback: &mut Playback, keycode: Keycode) {
+    match keycode {
+        Keycode::K | Keycode::Space => playback.toggle_pause(),
+        Keycode::LeftBracket => playback.set_speed(playback.speed() / 2.),
+        Keycode::RightBracket => playback.set_speed(playback.speed() * 2.),
+        Keycode::Period => playback.step_frames(1),
+        Keycode::Comma => playback.step_frames(-1),
+        Keycode::J => playback.seek(playback.seconds() - 5.),
+        Keycode::L => playback.seek(playback.seconds() + 5.),
+        _ => {
+            // number keys seek to tenths of the replay
+            let digit = (keycode as i32) - (Keycode::Num0 as i32);
+            if (0..10).contains(&digit) {
+                playback.seek(playback.duration() * f64::from(digit) / 10.);
+            }
+        }
+    }
+}
+
+pub fn display_game(mut source: Source) -> Result<(), Box<dyn std::error::Error>> {
     let sdl_context = sdl2::init()?;
     let video_subsystem = sdl_context.video()?;
 
@@ -78,26 +214,61 @@ pub fn display_game(
 
     let mut canvas_drawer = CanvasDrawer {
         canvas: window.into_canvas().build().map_err(|e| e.to_string())?,
+        camera: Camera::default(),
     };
+    let mut follow_next = false;
+    let mut message = None;
+    let mut rotational = false;
+    let mut tethered = false;
 
     let mut event_pump = sdl_context.event_pump()?;
+    let mut last_frame = Instant::now();
 
     'running: loop {
-        let (mut is_move, mut direction) = (false, 0.);
+        let mut action = Action::default();
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit { .. } => break 'running,
                 Event::KeyDown {
                     keycode: Some(keycode),
+                    repeat,
                     ..
                 } => {
-                    (is_move, direction) = match keycode {
+                    let camera = &mut canvas_drawer.camera;
+                    match keycode {
                         Keycode::Escape => break 'running,
-                        Keycode::W => (true, -std::f64::consts::PI / 2.),
-                        Keycode::A => (true, std::f64::consts::PI),
-                        Keycode::S => (true, std::f64::consts::PI / 2.),
-                        Keycode::D => (true, 0.),
-                        _ => (false, 0.),
+                        Keycode::W if rotational => action.direction = Some(0.),
+                        Keycode::A if rotational => action.turn = -1.,
+                        Keycode::S if rotational => action.reverse = true,
+                        Keycode::D if rotational => action.turn = 1.,
+                        Keycode::W => action.direction = Some(-std::f64::consts::PI / 2.),
+                        Keycode::A => action.direction = Some(std::f64::consts::PI),
+                        Keycode::S => action.direction = Some(std::f64::consts::PI / 2.),
+                        Keycode::D => action.direction = Some(0.),
+                        Keycode::E => action.mine = true,
+                        Keycode::Q => action.anchor = true,
+                        // repeats of a held key would release what it just latched
+                        Keycode::T if !repeat => {
+                            action.tether = Some(match tethered {
+                                true => TetherAction::Release,
+                                false => TetherAction::Latch,
+                            })
+                        }
+                        Keycode::P => action.role = Some(Role::Play),
+                        Keycode::O => action.role = Some(Role::Spectate),
+                        Keycode::F => follow_next = true,
+                        Keycode::Up => camera.pan(0., -CAMERA_PAN),
+                        Keycode::Down => camera.pan(0., CAMERA_PAN),
+                        Keycode::Left => camera.pan(-CAMERA_PAN, 0.),
+                        Keycode::Right => camera.pan(CAMERA_PAN, 0.),
+                        Keycode::Equals => camera.zoom *= CAMERA_ZOOM,
+                        Keycode::Minus => camera.zoom /= CAMERA_ZOOM,
+                        Keycode::Home => *camera = Camera::default(),
+                        _ => {
+                            if let Source::Replay(playback) = &mut source {
+                                control_playback(playback, keycode);
+                            }
+                        }
                     }
                 }
                 _ => {}
@@ -107,15 +278,50 @@ pub fn display_game(
         canvas_drawer.canvas.set_draw_color(Color::BLACK);
         canvas_drawer.canvas.clear();
 
-        let msg = state_recv.recv()?;
+        let (own_id, msg) = match &mut source {
+            Source::Live { state_recv, .. } => state_recv.recv()?,
+            Source::Replay(playback) => {
+                let frame = Duration::from_secs_f64(FRAME_SECONDS);
+                std::thread::sleep(frame.saturating_sub(last_frame.elapsed()));
+                playback.advance(last_frame.elapsed().as_secs_f64());
+                last_frame = Instant::now();
+                (None, playback.state())
+            }
+        };
         let space: Space = bincode::deserialize(&msg)?;
+        rotational = matches!(
+            space.ship_config().controls,
+            ControlModel::Rotational { .. }
+        );
+        tethered = own_id.is_some_and(|id| space.tethers().any(|tether| tether.owner == id));
+        // show server messages in the window title
+        if space.get_message() != message.as_deref() {
+            message = space.get_message().map(String::from);
+            let title = match &message {
+                Some(message) => format!("Space Game - {message}"),
+                None => String::from("Space Game"),
+            };
+            canvas_drawer
+                .canvas
+                .window_mut()
+                .set_title(&title)
+                .map_err(|e| e.to_string())?;
+        }
+        if follow_next {
+            canvas_drawer.camera.follow_next(&space, own_id);
+            follow_next = false;
+        }
+        canvas_drawer.camera.update(&space);
         space.draw_all(&mut canvas_drawer, WIDTH, HEIGHT)?;
+        if let Some((energy, capacity)) = own_id.and_then(|id| space.fuel(id)) {
+            draw_gauge(&mut canvas_drawer.canvas, energy / capacity)?;
+        }
         canvas_drawer.canvas.present();
 
         // send move command
-        let mut msg = vec![is_move.into()];
-        msg.append(&mut f64::to_be_bytes(direction).to_vec());
-        command_send.send(msg)?;
+        if let Source::Live { command_send, .. } = &source {
+            command_send.send(action.to_bytes())?;
+        }
     }
 
     Ok(())
@@ -1,41 +1,50 @@
+pub mod bot;
+#[cfg(feature = "display")]
 mod display;
+pub mod replay;
 
-use std::io::{BufReader, Read, Write};
+use std::io::{Read, Write};
 use std::net::TcpStream;
-use std::sync::mpsc;
-use std::sync::mpsc::{Receiver, Sender};
-use std::thread;
+#[cfg(feature = "display")]
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
 
-pub fn run(ip: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let stream = TcpStream::connect(ip)?;
+use server::protocol;
+
+pub use bot::{run_bot, Action, Bot, Observation, Role, TetherAction};
+pub use replay::Playback;
+
+#[cfg(feature = "display")]
+pub fn run(ip: &str, role: Role) -> Result<(), Box<dyn std::error::Error>> {
+    let stream = connect(ip, role)?;
 
     let (state_send, state_recv) = mpsc::channel();
     let (command_send, command_recv) = mpsc::channel();
 
     thread::spawn(move || handle_connection(stream, state_send, command_recv));
 
-    display::display_game(state_recv, command_send)
+    display::display_game(display::Source::Live {
+        state_recv,
+        command_send,
+    })
 }
 
+#[cfg(feature = "display")]
+pub fn run_replay(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    display::display_game(display::Source::Replay(Box::new(Playback::open(path)?)))
+}
+
+#[cfg(feature = "display")]
 fn handle_connection<T: Write + Read>(
     mut stream: T,
-    state_sender: Sender<Vec<u8>>,
+    state_sender: Sender<(Option<u8>, Vec<u8>)>,
     command_receiver: Receiver<Vec<u8>>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     loop {
-        let mut buf_reader = BufReader::new(&mut stream);
-        // read msg length
-        let mut length_bytes = [0; std::mem::size_of::<u32>()];
-        buf_reader.read_exact(&mut length_bytes)?;
-
-        // read state
-        let length = u32::from_be_bytes(length_bytes);
-        let mut msg_buf = buf_reader.take(length.into());
-        let mut msg = Vec::new();
-        msg_buf.read_to_end(&mut msg)?;
-
         // send state to display
-        state_sender.send(msg)?;
+        state_sender.send(read_state(&mut stream)?)?;
 
         // receive last command from display
         let mut response_msg = command_receiver.recv()?;
@@ -47,3 +56,59 @@ fn handle_connection<T: Write + Read>(
         stream.write_all(&response_msg)?;
     }
 }
+
+fn connect(ip: &str, role: Role) -> std::io::Result<TcpStream> {
+    let mut stream = TcpStream::connect(ip)?;
+    let role = match role {
+        Role::Play => protocol::JOIN_PLAY,
+        Role::Spectate => protocol::JOIN_SPECTATE,
+    };
+    stream.write_all(&[role])?;
+    Ok(stream)
+}
+
+/// Reads a state frame, returns own ship id and the encoded `Space`.
+/// Nothing past the frame is read, so the next frame stays on the stream.
+fn read_state<T: Read>(stream: &mut T) -> std::io::Result<(Option<u8>, Vec<u8>)> {
+    // read msg length
+    let mut length_bytes = [0; std::mem::size_of::<u32>()];
+    stream.read_exact(&mut length_bytes)?;
+
+    let length = u32::from_be_bytes(length_bytes) as usize;
+    if length == 0 || length > protocol::MAX_STATE_SIZE {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid state frame length {length}"),
+        ));
+    }
+
+    // read ship id and state
+    let mut id = [0; 1];
+    stream.read_exact(&mut id)?;
+    let mut msg = vec![0; length - 1];
+    stream.read_exact(&mut msg)?;
+    Ok(((id[0] != protocol::NO_SHIP).then_some(id[0]), msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(length: u32, content: &[u8]) -> Vec<u8> {
+        let mut frame = length.to_be_bytes().to_vec();
+        frame.extend(content);
+        frame
+    }
+
+    #[test]
+    fn read_state_frames() {
+        let mut stream = std::io::Cursor::new(frame(3, &[2, 7, 8]));
+        assert_eq!((Some(2), vec![7, 8]), read_state(&mut stream).unwrap());
+        let mut stream = std::io::Cursor::new(frame(1, &[protocol::NO_SHIP]));
+        assert_eq!((None, vec![]), read_state(&mut stream).unwrap());
+        for length in [0, u32::MAX] {
+            let error = read_state(&mut std::io::Cursor::new(frame(length, &[1]))).unwrap_err();
+            assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+        }
+    }
+}
@@ -7,12 +7,29 @@ struct Args {
     /// Ip address to bind
     #[arg(long, default_value_t = String::from("127.0.0.1:8888"))]
     addr: String,
+
+    /// Join as a spectator without a ship
+    #[arg(long)]
+    spectate: bool,
+
+    /// Path to a replay file to play instead of connecting
+    #[arg(long)]
+    replay: Option<String>,
 }
 
 fn main() {
     let args = Args::parse();
 
-    if let Err(e) = client::run(&args.addr) {
+    let role = if args.spectate {
+        client::Role::Spectate
+    } else {
+        client::Role::Play
+    };
+    let result = match args.replay {
+        Some(path) => client::run_replay(&path),
+        None => client::run(&args.addr, role),
+    };
+    if let Err(e) = result {
         eprintln!("Client error: {e}");
         std::process::exit(1);
     }
@@ -0,0 +1,93 @@
+use server::{Replay, Space};
+
+pub const MIN_SPEED: f64 = 0.25;
+pub const MAX_SPEED: f64 = 8.;
+/// Replay time shown by a single displayed frame at normal speed
+pub const FRAME_SECONDS: f64 = 0.01;
+
+/// Plays a recorded replay back in real time.
+pub struct Playback {
+    replay: Replay,
+    space: Space,
+    speed: f64,
+    paused: bool,
+    pending_ticks: f64,
+}
+
+impl Playback {
+    pub fn open(path: &str) -> Result<Playback, Box<dyn std::error::Error>> {
+        let replay = Replay::read(path)?;
+        let space = replay.state_at(replay.first_tick());
+        Ok(Playback {
+            replay,
+            space,
+            speed: 1.,
+            paused: false,
+            pending_ticks: 0.,
+        })
+    }
+
+    /// Replay time of the shown state in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.ticks_to_seconds(self.space.get_tick() - self.replay.first_tick())
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.ticks_to_seconds(self.replay.last_tick() - self.replay.first_tick())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.pending_ticks = 0.;
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    /// Plays `seconds` of wall clock time at the current speed.
+    pub fn advance(&mut self, seconds: f64) {
+        if self.paused {
+            return;
+        }
+        self.pending_ticks += seconds * self.speed / self.replay.header.tick_seconds;
+        while self.pending_ticks >= 1. && self.space.get_tick() < self.replay.last_tick() {
+            self.replay.step(&mut self.space);
+            self.pending_ticks -= 1.;
+        }
+        if self.space.get_tick() >= self.replay.last_tick() {
+            self.pending_ticks = 0.;
+        }
+    }
+
+    /// Moves `frames` displayed frames forward or backward.
+    pub fn step_frames(&mut self, frames: i32) {
+        self.seek(self.seconds() + f64::from(frames) * FRAME_SECONDS);
+    }
+
+    /// Jumps to `seconds` from the replay start, replaying from the closest keyframe.
+    pub fn seek(&mut self, seconds: f64) {
+        let seconds = seconds.clamp(0., self.duration());
+        let ticks = (seconds / self.replay.header.tick_seconds).round() as u64;
+        self.replay
+            .seek(&mut self.space, self.replay.first_tick() + ticks);
+        self.pending_ticks = 0.;
+    }
+
+    /// Current state encoded like the states sent by the server.
+    pub fn state(&self) -> Vec<u8> {
+        self.space.get_state_binary()
+    }
+
+    fn ticks_to_seconds(&self, ticks: u64) -> f64 {
+        ticks as f64 * self.replay.header.tick_seconds
+    }
+}
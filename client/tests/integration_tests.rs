@@ -0,0 +1,131 @@
+use std::net::TcpStream;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use client::{run_bot, Action, Observation, Playback, Role};
+
+fn start_server(addr: &'static str, record: Option<String>) {
+    let config = server::Config {
+        paths: vec![String::from("../maps/example.json")],
+        addr: String::from(addr),
+        seed: Some(1),
+        record,
+        ..Default::default()
+    };
+    thread::spawn(move || server::run(&config).map_err(|e| e.to_string()));
+    while TcpStream::connect(addr).is_err() {
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Waits until the replay recorded to `dir` in the background reaches `seconds`.
+fn wait_for_replay(dir: &Path, seconds: f64) -> Playback {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    loop {
+        let playback = std::fs::read_dir(dir)
+            .ok()
+            .and_then(|mut entries| entries.next()?.ok())
+            .and_then(|entry| Playback::open(entry.path().to_str()?).ok());
+        if let Some(playback) = playback.filter(|playback| playback.duration() >= seconds) {
+            return playback;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "replay did not reach {seconds} seconds"
+        );
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn scripted_bots_play() {
+    let addr = "127.0.0.1:18881";
+    start_server(addr, None);
+
+    let handles: Vec<_> = (0..2)
+        .map(|i| {
+            thread::spawn(move || {
+                let mut seen_own_ship = false;
+                let mut bot = |observation: &Observation| {
+                    seen_own_ship |= observation.ship().is_some();
+                    assert_eq!(4, observation.planets().count());
+                    Action {
+                        direction: Some(i as f64),
+                        role: None,
+                        ..Action::default()
+                    }
+                };
+                let id = run_bot(addr, Role::Play, &mut bot, Some(50))
+                    .unwrap()
+                    .unwrap();
+                (id, seen_own_ship)
+            })
+        })
+        .collect();
+
+    let mut ids: Vec<u8> = handles
+        .into_iter()
+        .map(|handle| {
+            let (id, seen_own_ship) = handle.join().unwrap();
+            assert!(seen_own_ship);
+            id
+        })
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(2, ids.len());
+}
+
+#[test]
+fn spectator_switches_to_playing() {
+    let addr = "127.0.0.1:18882";
+    start_server(addr, None);
+
+    let mut bot = |observation: &Observation| {
+        assert_eq!(4, observation.planets().count());
+        if observation.tick < 10 {
+            assert!(observation.id.is_none());
+        }
+        Action {
+            direction: None,
+            role: (observation.tick == 10).then_some(Role::Play),
+            ..Action::default()
+        }
+    };
+    let id = run_bot(addr, Role::Spectate, &mut bot, Some(20)).unwrap();
+    assert!(id.is_some());
+}
+
+#[test]
+fn recorded_game_plays_back() {
+    let addr = "127.0.0.1:18883";
+    let dir = std::env::temp_dir().join("space_game_playback_test");
+    std::fs::remove_dir_all(&dir).ok();
+    start_server(addr, Some(dir.to_str().unwrap().to_string()));
+
+    let mut bot = |_: &Observation| Action {
+        direction: Some(0.),
+        role: None,
+        ..Action::default()
+    };
+    run_bot(addr, Role::Play, &mut bot, Some(300)).unwrap();
+
+    let mut playback = wait_for_replay(&dir, 1.);
+    playback.advance(0.5);
+    assert!((playback.seconds() - 0.5).abs() < 1e-6);
+    playback.set_speed(100.);
+    assert_eq!(8., playback.speed());
+    playback.toggle_pause();
+    playback.advance(1.);
+    assert!((playback.seconds() - 0.5).abs() < 1e-6);
+    playback.step_frames(-1);
+    assert!((playback.seconds() - 0.49).abs() < 1e-6);
+
+    let state = playback.state();
+    playback.seek(playback.duration());
+    playback.seek(0.49);
+    assert_eq!(state, playback.state());
+    let space: server::Space = bincode::deserialize(&playback.state()).unwrap();
+    assert_eq!(4, space.planets().count());
+}
@@ -1,3 +1,7 @@
 pub const GAME_UPDATE_TICK_SECONDS: f64 = 0.001;
 pub const GAME_STATE_TICK_SECONDS: f64 = 0.01;
 pub const MAX_PLAYERS: u8 = 8;
+pub const MAX_SPECTATORS: u8 = 32;
+pub const HANDSHAKE_SECONDS: f64 = 5.;
+pub const REPLAY_KEYFRAME_TICKS: u64 = 1000;
+pub const MAP_WATCH_SECONDS: f64 = 1.;
@@ -3,39 +3,247 @@ mod logic;
 mod networking;
 mod ui;
 
+pub use logic::control::{ControlModel, Steering};
+pub use logic::env::{
+    survival_reward, Env, EnvConfig, ObservationConfig, RewardFn, StepResult, VecEnv,
+};
+pub use logic::generate::{generate, GeneratorConfig, Interval};
+pub use logic::map::Map;
+pub use logic::object::Object;
+pub use logic::replay::{Replay, ReplayHeader};
 pub use logic::space::Space;
-pub use ui::display::{DisplayInfo, DisplayType, Drawer};
+pub use networking::protocol;
+pub use ui::display::{DisplayInfo, DisplayType, Drawer, LineInfo};
 
 use bus::Bus;
-use std::fs;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-use logic::update::{run_game, run_state_send};
-use networking::connection::handle_listen;
+use logic::checkpoint::{self, run_checkpoints};
+use logic::map::{self, run_maps, MapRotation};
+use logic::replay::ReplayRecorder;
+use logic::update::{run_game, run_state_send, Clock};
+use networking::connection::{handle_listen, Players};
+use ui::admin::Admin;
+
+pub struct Config {
+    /// Map files or directories of them, played in rotation
+    pub paths: Vec<String>,
+    /// Seconds of game time a match lasts before switching to the next map, the map
+    /// only reloads on changes if unset
+    pub match_seconds: Option<f64>,
+    /// Ip address to bind
+    pub addr: String,
+    /// Seed for all randomness, overrides the map seed
+    pub seed: Option<u64>,
+    /// Directory to record replays to
+    pub record: Option<String>,
+    /// Checkpoint file to continue a saved game from instead of the map
+    pub resume: Option<String>,
+    /// Checkpoint file to periodically save the game to
+    pub checkpoint: Option<String>,
+    pub checkpoint_seconds: f64,
+    /// Read admin commands from stdin
+    pub admin: bool,
+    /// Unix socket to accept admin commands on
+    pub admin_socket: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            paths: vec![String::from("maps/example.json")],
+            match_seconds: None,
+            addr: String::from("0.0.0.0:8888"),
+            seed: None,
+            record: None,
+            resume: None,
+            checkpoint: None,
+            checkpoint_seconds: 60.,
+            admin: false,
+            admin_socket: None,
+        }
+    }
+}
+
+/// JSON Schema of the map format.
+pub fn map_schema() -> String {
+    map::schema()
+}
+
+/// Loads a map and reports every problem found in it.
+pub fn validate_map(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    map::load(std::path::Path::new(path))?;
+    Ok(())
+}
+
+/// Translates a map between JSON, TOML and RON, chosen by the file extensions.
+pub fn convert_map(input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let map = map::read(std::path::Path::new(input))?;
+    map::write(&map, std::path::Path::new(output))
+}
+
+/// Writes a map as json, toml or ron, chosen by the file extension.
+pub fn write_map(map: &Map, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    map::write(map, std::path::Path::new(path))
+}
+
+/// Game set up by `start`, accepting players once served.
+pub struct Server {
+    pub seed: u64,
+    pub tick: u64,
+    /// Name of the map from its metadata, unset when resuming
+    pub map_name: Option<String>,
+    /// File the replay is recorded to
+    pub replay: Option<std::path::PathBuf>,
+    addr: String,
+    command_sender: mpsc::Sender<ui::command::Command>,
+    state_bus: Arc<Mutex<Bus<Arc<Space>>>>,
+    players: Arc<Mutex<Players>>,
+    handles: Vec<thread::JoinHandle<()>>,
+}
+
+impl Server {
+    /// Listens for connections until the listener fails.
+    pub fn serve(self) -> Result<(), Box<dyn std::error::Error>> {
+        handle_listen(
+            &self.addr,
+            self.command_sender,
+            &self.state_bus,
+            &self.players,
+        )?;
+        for handle in self.handles {
+            handle.join().unwrap();
+        }
+        Ok(())
+    }
+}
+
+pub fn run(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    start(config)?.serve()
+}
+
+/// Loads the game and starts its threads without accepting players yet.
+pub fn start(config: &Config) -> Result<Server, Box<dyn std::error::Error>> {
+    if config.checkpoint_seconds.is_nan() || config.checkpoint_seconds <= 0. {
+        return Err(format!(
+            "checkpoint seconds must be positive, found {}",
+            config.checkpoint_seconds
+        )
+        .into());
+    }
+    if config
+        .match_seconds
+        .is_some_and(|seconds| seconds.is_nan() || seconds <= 0.)
+    {
+        return Err("match seconds must be positive".into());
+    }
+    let mut rotation = MapRotation::new(map::map_paths(&config.paths)?);
 
-pub fn run(path: &str, addr: &str) -> Result<(), Box<dyn std::error::Error>> {
     // create space
-    let space: Space = serde_json::from_slice(&fs::read(path)?)?;
+    let mut map_name = None;
+    let space = match &config.resume {
+        Some(path) => {
+            // ships of players who do not reconnect drift until their slot is reused
+            let mut space = checkpoint::load(path)?;
+            space.clear_input();
+            rotation.resume(space.get_tick());
+            space
+        }
+        None => {
+            let map = map::read(rotation.current())?;
+            map_name = map.metadata.name.clone();
+            let mut space = map.into_space();
+            let seed = config
+                .seed
+                .or(space.get_seed())
+                .unwrap_or_else(rand::random);
+            space.seed(seed);
+            space
+        }
+    };
+    let seed = space.get_seed().unwrap_or_default();
+    let tick = space.get_tick();
+
+    // start replay recording
+    let mut recorder = None;
+    let mut replay = None;
+    if let Some(dir) = &config.record {
+        let map = match &config.resume {
+            Some(path) => path.clone(),
+            None => rotation.current().display().to_string(),
+        };
+        let header = ReplayHeader::new(&map, seed);
+        let (replay_recorder, path) = ReplayRecorder::create(dir, &header, &space)?;
+        replay = Some(path);
+        recorder = Some(replay_recorder);
+    }
     let space_counter = Arc::new(Mutex::new(space));
+    let clock = Arc::new(Mutex::new(Clock::default()));
 
     // update space logic thread
     let update_counter = Arc::clone(&space_counter);
+    let update_clock = Arc::clone(&clock);
     let (command_sender, command_receiver) = mpsc::channel();
-    let update_handle = thread::spawn(move || run_game(&update_counter, command_receiver));
+    let update_handle =
+        thread::spawn(move || run_game(&update_counter, &update_clock, command_receiver, recorder));
 
     // communication space thread
-    let state_bus = Arc::new(Mutex::new(Bus::new(constants::MAX_PLAYERS.into())));
-    let space_counter = Arc::clone(&space_counter);
+    let state_bus = Arc::new(Mutex::new(Bus::new(
+        usize::from(constants::MAX_PLAYERS) + usize::from(constants::MAX_SPECTATORS),
+    )));
+    let state_counter = Arc::clone(&space_counter);
     let broadcast = Arc::clone(&state_bus);
-    let state_handle = thread::spawn(move || run_state_send(&space_counter, &broadcast));
+    let state_handle = thread::spawn(move || run_state_send(&state_counter, &clock, &broadcast));
 
-    // listen for new connections
-    handle_listen(addr, command_sender, &state_bus)?;
+    // periodic checkpoint thread
+    if let Some(path) = config.checkpoint.clone() {
+        let checkpoint_counter = Arc::clone(&space_counter);
+        let interval = config.checkpoint_seconds;
+        thread::spawn(move || run_checkpoints(&checkpoint_counter, &path, interval));
+    }
 
-    update_handle.join().unwrap();
-    state_handle.join().unwrap();
+    // map rotation and reload thread
+    let rotation = Arc::new(Mutex::new(rotation));
+    let map_sender = command_sender.clone();
+    let map_rotation = Arc::clone(&rotation);
+    let map_counter = Arc::clone(&space_counter);
+    let match_seconds = config.match_seconds;
+    thread::spawn(move || run_maps(&map_sender, &map_rotation, &map_counter, match_seconds));
 
-    Ok(())
+    // admin console threads
+    let players = Arc::new(Mutex::new(Players::new(constants::MAX_PLAYERS)));
+    let admin = Admin {
+        command_sender: command_sender.clone(),
+        players: Arc::clone(&players),
+        space_counter: Arc::clone(&space_counter),
+        rotation,
+    };
+    if config.admin {
+        let admin = admin.clone();
+        thread::spawn(move || admin.run_console(std::io::stdin().lock(), std::io::stdout()));
+    }
+    if let Some(path) = config.admin_socket.clone() {
+        #[cfg(unix)]
+        thread::spawn(move || {
+            if let Err(e) = admin.run_socket(&path) {
+                eprintln!("Admin socket error: {e}");
+            }
+        });
+        #[cfg(not(unix))]
+        eprintln!("Admin socket {path} is only supported on unix");
+    }
+
+    Ok(Server {
+        seed,
+        tick,
+        map_name,
+        replay,
+        addr: config.addr.clone(),
+        command_sender,
+        state_bus,
+        players,
+        handles: vec![update_handle, state_handle],
+    })
 }
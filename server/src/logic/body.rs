@@ -0,0 +1,99 @@
+use glam::DVec2;
+use serde::{Deserialize, Serialize};
+
+use super::object::Object;
+
+/// Free distance kept when leaving a wormhole so the exit does not swallow it again
+const WORMHOLE_GAP: f64 = 1e-6;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum BodyKind {
+    #[default]
+    Planet,
+    /// Destroys ships within `horizon` of its center
+    BlackHole { horizon: f64 },
+    /// Pushes everything away with a negative field
+    Repulsor,
+    /// Sends objects entering it out of the planet at index `exit`
+    Wormhole { exit: usize },
+}
+
+/// Moves `object` from wormhole `entry` to `exit`, keeping its velocity relative to them.
+pub fn teleport(object: &mut Object, entry: &Object, exit: &Object) {
+    let relative_velocity = object.velocity - entry.velocity;
+    let direction = relative_velocity
+        .try_normalize()
+        .or((object.location - entry.location).try_normalize())
+        .unwrap_or(DVec2::X);
+    object.location = exit.location + direction * (exit.radius + object.radius + WORMHOLE_GAP);
+    object.velocity = exit.velocity + relative_velocity;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{
+        motion::Motion,
+        object::Update,
+        space::{ShipConfig, Space},
+    };
+
+    fn space() -> Space {
+        Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn teleport_keeps_relative_velocity() {
+        let entry = Object::new(DVec2::new(0.2, 0.2), 0.05, 1., 0., DVec2::new(0.1, 0.));
+        let exit = Object::new(DVec2::new(0.8, 0.8), 0.05, 1., 0., DVec2::new(0., 0.2));
+        let mut ship = Object::new(DVec2::new(0.16, 0.2), 0.01, 1., 0., DVec2::new(0.5, 0.));
+        teleport(&mut ship, &entry, &exit);
+        assert_eq!(DVec2::new(0.4, 0.2), ship.velocity);
+        assert!(ship.location.distance(DVec2::new(0.86, 0.8)) < 1e-5);
+    }
+
+    #[test]
+    fn black_hole_destroys_ships_within_horizon() {
+        let mut space = space();
+        space.add_planet(DVec2::new(0.5, 0.5), 1., 0., 0.01, DVec2::ZERO);
+        space.set_planet_kind(0, BodyKind::BlackHole { horizon: 0.2 });
+        space.add_ship(1, DVec2::new(0.75, 0.5));
+        space.add_ship(2, DVec2::new(0.85, 0.5));
+        space.update(0.);
+        assert_eq!(Some(1), space.deaths(1));
+        assert_eq!(Some(0), space.deaths(2));
+    }
+
+    #[test]
+    fn repulsor_pushes_ships_away() {
+        let mut space = space();
+        space.add_planet(DVec2::new(0.5, 0.5), 1., 1., 0.05, DVec2::ZERO);
+        space.set_planet_kind(0, BodyKind::Repulsor);
+        space.set_planet_motion(0, Motion::Anchored);
+        space.add_ship(1, DVec2::new(0.8, 0.5));
+        space.update(0.01);
+        assert!(space.ship(1).unwrap().velocity.x > 0.);
+    }
+
+    #[test]
+    fn wormhole_moves_ships_to_exit() {
+        let mut space = space();
+        space.add_planet(DVec2::new(0.2, 0.5), 0., 0., 0.05, DVec2::ZERO);
+        space.add_planet(DVec2::new(0.8, 0.5), 0., 0., 0.05, DVec2::ZERO);
+        space.set_planet_kind(0, BodyKind::Wormhole { exit: 1 });
+        space.set_planet_kind(1, BodyKind::Wormhole { exit: 0 });
+        space.add_ship(1, DVec2::new(0.2, 0.35));
+        space.ship_mut(1).unwrap().object_mut().velocity = DVec2::new(0., 1.);
+        space.update(0.01);
+        let ship = space.ship(1).unwrap();
+        assert!(ship.location.distance(DVec2::new(0.8, 0.65)) < 1e-5);
+        assert_eq!(DVec2::new(0., 1.), ship.velocity);
+        assert_eq!(Some(0), space.deaths(1));
+    }
+}
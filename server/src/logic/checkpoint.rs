@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::space::{FullState, Space};
+
+fn is_json(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+/// Writes the full simulation state, as JSON for `.json` files and bincode otherwise.
+/// The file is replaced atomically so a crash never leaves a partial checkpoint.
+pub fn save(space: &Space, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let state = FullState(space.clone());
+    let data = if is_json(path) {
+        serde_json::to_vec_pretty(&state)?
+    } else {
+        bincode::serialize(&state)?
+    };
+    let tmp_path = format!("{path}.tmp");
+    fs::write(&tmp_path, data)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+pub fn load(path: &str) -> Result<Space, Box<dyn std::error::Error>> {
+    let data = fs::read(path)?;
+    let state: FullState = if is_json(path) {
+        serde_json::from_slice(&data)?
+    } else {
+        bincode::deserialize(&data)?
+    };
+    Ok(state.0)
+}
+
+pub fn run_checkpoints(space_counter: &Arc<Mutex<Space>>, path: &str, interval_seconds: f64) {
+    loop {
+        std::thread::sleep(Duration::from_secs_f64(interval_seconds));
+        let space = space_counter.lock().unwrap().clone();
+        if let Err(e) = save(&space, path) {
+            eprintln!("Checkpoint error: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::ShipConfig;
+    use glam::DVec2;
+
+    fn played_space() -> Space {
+        let mut space = Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.01,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        });
+        space.seed(5);
+        space.add_planet(DVec2::new(0.5, 0.5), 1., 0.1, 0.1, DVec2::ZERO);
+        for id in 1..=3 {
+            let location = space.gen_new_location();
+            space.add_ship(id, location);
+            space.move_ship(id, Some(f64::from(id)));
+        }
+        for _ in 0..500 {
+            space.update(0.01);
+        }
+        space
+    }
+
+    fn assert_resumes(file: &str) {
+        let path = std::env::temp_dir().join(file);
+        let path = path.to_str().unwrap();
+        let mut space = played_space();
+        save(&space, path).unwrap();
+        let mut resumed = load(path).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(space.get_tick(), resumed.get_tick());
+        for _ in 0..500 {
+            space.update(0.01);
+            resumed.update(0.01);
+        }
+        assert_eq!(space.get_state_binary(), resumed.get_state_binary());
+        // the rng continues where it was saved
+        assert_eq!(space.gen_new_location(), resumed.gen_new_location());
+    }
+
+    #[test]
+    fn resume_from_bincode_checkpoint() {
+        assert_resumes("space_game_checkpoint_test.bin");
+    }
+
+    #[test]
+    fn resume_from_json_checkpoint() {
+        assert_resumes("space_game_checkpoint_test.json");
+    }
+}
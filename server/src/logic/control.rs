@@ -0,0 +1,184 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How players steer their ships.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ControlModel {
+    /// Thrust in any direction at once
+    #[default]
+    Direct,
+    /// Turn the heading and thrust forward or in reverse along it
+    Rotational {
+        /// Angular acceleration in radians per second²
+        torque: f64,
+        /// Maximal angular speed in radians per second
+        max_spin: f64,
+        /// Part of the force available in reverse
+        #[serde(default)]
+        reverse: f64,
+    },
+}
+
+/// Input of a ship under rotational control, values from -1 to 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Steering {
+    /// Forward when positive, reverse when negative
+    pub thrust: f64,
+    /// Towards increasing angles when positive
+    pub turn: f64,
+}
+
+/// Heading and angular speed after `time` seconds of turning with `steering`.
+/// Without input the spin is stopped with the same torque.
+pub fn rotate(
+    heading: f64,
+    spin: f64,
+    steering: Steering,
+    torque: f64,
+    max_spin: f64,
+    time: f64,
+) -> (f64, f64) {
+    let turn = steering.turn.clamp(-1., 1.);
+    let spin = if turn != 0. {
+        (spin + turn * torque * time).clamp(-max_spin, max_spin)
+    } else {
+        let change = (torque * time).min(spin.abs());
+        spin - change * spin.signum()
+    };
+    (
+        (heading + spin * time).rem_euclid(std::f64::consts::TAU),
+        spin,
+    )
+}
+
+/// Steering that turns a ship towards `direction`, slowing its spin in time to stop
+/// facing it, and thrusts forward the more it faces it. `None` lets it drift.
+pub fn steer_towards(
+    heading: f64,
+    spin: f64,
+    direction: Option<f64>,
+    torque: f64,
+    max_spin: f64,
+) -> Steering {
+    let Some(direction) = direction else {
+        return Steering::default();
+    };
+    let offset = (direction - heading + std::f64::consts::PI).rem_euclid(std::f64::consts::TAU)
+        - std::f64::consts::PI;
+    // fastest spin that can still be stopped by the torque over the remaining angle
+    let target_spin = offset.signum() * (2. * torque * offset.abs()).sqrt().min(max_spin);
+    Steering {
+        thrust: offset.cos().max(0.),
+        // full torque once the spin is 0.1 radians per second off
+        turn: ((target_spin - spin) * 10.).clamp(-1., 1.),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::{ShipConfig, Space};
+    use glam::DVec2;
+
+    fn space(controls: ControlModel) -> Space {
+        Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            controls,
+            ..Default::default()
+        })
+    }
+
+    fn steering(turn: f64) -> Steering {
+        Steering { thrust: 0., turn }
+    }
+
+    #[test]
+    fn turning_is_limited_by_torque_and_max_spin() {
+        let (heading, spin) = rotate(0., 0., steering(1.), 2., 3., 0.5);
+        assert_eq!((0.5, 1.), (heading, spin));
+        let (_, spin) = rotate(heading, spin, steering(1.), 2., 3., 5.);
+        assert_eq!(3., spin);
+        let (heading, spin) = rotate(0.1, 0., steering(-1.), 2., 3., 0.5);
+        assert!((heading - (0.1 - 0.5 + std::f64::consts::TAU)).abs() < 1e-9);
+        assert_eq!(-1., spin);
+    }
+
+    #[test]
+    fn spin_stops_without_input() {
+        let (_, spin) = rotate(0., 1., steering(0.), 2., 3., 0.25);
+        assert_eq!(0.5, spin);
+        let (heading, spin) = rotate(1., 1., steering(0.), 2., 3., 1.);
+        assert_eq!((1., 0.), (heading, spin));
+    }
+
+    #[test]
+    fn steer_towards_direction() {
+        assert_eq!(Steering::default(), steer_towards(1., 0., None, 2., 3.));
+        let facing = steer_towards(1., 0., Some(1.), 2., 3.);
+        assert_eq!(
+            Steering {
+                thrust: 1.,
+                turn: 0.
+            },
+            facing
+        );
+        let behind = steer_towards(0., 0., Some(3.), 2., 3.);
+        assert_eq!((0., 1.), (behind.thrust, behind.turn));
+
+        let (mut heading, mut spin) = (0.1, 0.);
+        for _ in 0..3000 {
+            let steering = steer_towards(heading, spin, Some(4.), 2., 3.);
+            (heading, spin) = rotate(heading, spin, steering, 2., 3., 0.001);
+        }
+        assert!((heading - 4.).abs() < 0.01 && spin.abs() < 0.1);
+    }
+
+    #[test]
+    fn rotational_ships_thrust_along_heading() {
+        let mut space = space(ControlModel::Rotational {
+            torque: 10.,
+            max_spin: 5.,
+            reverse: 0.5,
+        });
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        // thrust directions of the direct model are ignored
+        space.move_ship(1, Some(1.));
+        space.steer_ship(
+            1,
+            Steering {
+                thrust: 1.,
+                turn: 0.,
+            },
+        );
+        space.update(0.1);
+        assert_eq!(Some(0.), space.heading(1));
+        assert_eq!(0., space.ship(1).unwrap().velocity.y);
+        assert!(space.ship(1).unwrap().velocity.x > 0.);
+
+        space.steer_ship(
+            1,
+            Steering {
+                thrust: -1.,
+                turn: 1.,
+            },
+        );
+        space.update(0.1);
+        let heading = space.heading(1).unwrap();
+        assert!((heading - 0.1).abs() < 1e-9);
+        let acceleration = space.ship(1).unwrap().acceleration;
+        assert!((acceleration - -0.025 * DVec2::from_angle(heading)).length() < 1e-9);
+    }
+
+    #[test]
+    fn direct_ships_face_their_thrust() {
+        let mut space = space(ControlModel::Direct);
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.move_ship(1, Some(2.));
+        space.move_ship(1, None);
+        assert_eq!(Some(2.), space.heading(1));
+    }
+}
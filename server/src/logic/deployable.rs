@@ -0,0 +1,203 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ui::display::{DisplayInfo, DisplayType};
+
+use super::object::{Object, Update};
+
+/// Mines a ship can drop, detonating when a ship comes close.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MineConfig {
+    /// Mines of a ship on the map at once
+    pub max: usize,
+    pub radius: f64,
+    /// Distance from the center at which ships set the mine off
+    pub trigger: f64,
+    /// Seconds before the mine goes live, so it spares the ship that dropped it
+    #[serde(default = "default_arm_seconds")]
+    pub arm_seconds: f64,
+}
+
+fn default_arm_seconds() -> f64 {
+    1.
+}
+
+/// Short-lived bodies a ship can drop that pull or push the ships of others.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnchorConfig {
+    /// Anchors of a ship on the map at once
+    pub max: usize,
+    pub radius: f64,
+    /// Attractive when positive, repulsive when negative
+    pub field: f64,
+    /// Seconds before the anchor vanishes
+    pub seconds: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeployKind {
+    Mine,
+    Anchor,
+}
+
+/// Static object dropped by a ship.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deployable {
+    pub kind: DeployKind,
+    pub owner: u8,
+    object: Object,
+    /// Seconds since it was dropped
+    age: f64,
+}
+
+impl Update for Deployable {
+    // deployables stay where they were dropped
+    fn update(&mut self, time: f64, _total_field: DVec2) {
+        self.age += time;
+    }
+
+    fn object(&self) -> &Object {
+        &self.object
+    }
+
+    fn object_mut(&mut self) -> &mut Object {
+        &mut self.object
+    }
+}
+
+impl Deployable {
+    pub fn mine(owner: u8, location: DVec2, config: &MineConfig) -> Deployable {
+        Deployable {
+            kind: DeployKind::Mine,
+            owner,
+            object: Object::new(location, config.radius, 0., 0., DVec2::ZERO),
+            age: 0.,
+        }
+    }
+
+    pub fn anchor(owner: u8, location: DVec2, config: &AnchorConfig) -> Deployable {
+        Deployable {
+            kind: DeployKind::Anchor,
+            owner,
+            object: Object::new(location, config.radius, 0., config.field, DVec2::ZERO),
+            age: 0.,
+        }
+    }
+
+    pub fn age(&self) -> f64 {
+        self.age
+    }
+
+    /// Whether a live mine is close enough to `other` to detonate.
+    pub fn triggered_by(&self, other: &impl Update, config: &MineConfig) -> bool {
+        self.kind == DeployKind::Mine
+            && self.age >= config.arm_seconds
+            && self.object.location.distance(other.object().location)
+                <= config.trigger + other.object().radius
+    }
+
+    pub fn get_display_info(&self) -> DisplayInfo {
+        DisplayInfo {
+            display_type: match self.kind {
+                DeployKind::Mine => DisplayType::Mine,
+                DeployKind::Anchor => DisplayType::Anchor,
+            },
+            id: Some(self.owner),
+            x: self.object.location.x,
+            y: self.object.location.y,
+            radius: self.object.radius,
+            heading: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::{ShipConfig, Space};
+
+    fn ship_config() -> ShipConfig {
+        ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mines_arm_before_triggering() {
+        let config = MineConfig {
+            max: 1,
+            radius: 0.005,
+            trigger: 0.05,
+            arm_seconds: 1.,
+        };
+        let mut mine = Deployable::mine(1, DVec2::new(0.5, 0.5), &config);
+        let near = Deployable::mine(2, DVec2::new(0.54, 0.5), &config);
+        let far = Deployable::mine(2, DVec2::new(0.6, 0.5), &config);
+        assert!(!mine.triggered_by(&near, &config));
+        mine.update(1., DVec2::new(1., 0.));
+        assert_eq!(DVec2::new(0.5, 0.5), mine.object().location);
+        assert!(mine.triggered_by(&near, &config));
+        assert!(!mine.triggered_by(&far, &config));
+    }
+
+    #[test]
+    fn mines_detonate_on_ships_close_by() {
+        let mut space = Space::new(ShipConfig {
+            mines: Some(MineConfig {
+                max: 2,
+                radius: 0.005,
+                trigger: 0.03,
+                arm_seconds: 0.5,
+            }),
+            ..ship_config()
+        });
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.add_ship(2, DVec2::new(0.2, 0.2));
+        for _ in 0..3 {
+            space.deploy(1, DeployKind::Mine);
+        }
+        space.deploy(1, DeployKind::Anchor);
+        assert_eq!(2, space.deployables().count());
+        // the mines spare their owner until they are armed
+        space.update(0.1);
+        assert_eq!(Some(0), space.deaths(1));
+        space.update(0.5);
+        assert_eq!(Some(1), space.deaths(1));
+        assert_eq!(0, space.deployables().count());
+
+        space.deploy(2, DeployKind::Mine);
+        space.remove_ship(2);
+        assert_eq!(0, space.deployables().count());
+    }
+
+    #[test]
+    fn anchors_bend_other_ships_and_expire() {
+        let mut space = Space::new(ShipConfig {
+            anchors: Some(AnchorConfig {
+                max: 1,
+                radius: 0.01,
+                field: -1.,
+                seconds: 0.3,
+            }),
+            ..ship_config()
+        });
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.add_ship(2, DVec2::new(0.8, 0.5));
+        space.deploy(1, DeployKind::Anchor);
+        space.deploy(1, DeployKind::Anchor);
+        assert_eq!(1, space.deployables().count());
+        space.update(0.1);
+        // repelled away from the anchor, while the owner is not affected
+        assert!(space.ship(2).unwrap().velocity.x > 0.);
+        assert_eq!(DVec2::ZERO, space.ship(1).unwrap().velocity);
+        space.update(0.3);
+        assert_eq!(0, space.deployables().count());
+    }
+}
@@ -0,0 +1,374 @@
+use glam::DVec2;
+use std::error::Error;
+use std::thread;
+
+use crate::constants;
+
+use super::control::{self, ControlModel};
+use super::space::Space;
+
+/// Observations, rewards per agent and whether the episode has ended.
+pub type StepResult = (Vec<Vec<f64>>, Vec<f64>, bool);
+
+/// Reward of a single agent after a step, given whether it crashed during it.
+pub type RewardFn = fn(space: &Space, id: u8, crashed: bool) -> f64;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ObservationConfig {
+    /// Planet locations relative to the ship
+    pub planet_positions: bool,
+    /// Ship velocity and planet velocities relative to the ship
+    pub velocities: bool,
+    /// Total field acting on the ship
+    pub fields: bool,
+}
+
+impl Default for ObservationConfig {
+    fn default() -> Self {
+        ObservationConfig {
+            planet_positions: true,
+            velocities: true,
+            fields: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EnvConfig {
+    pub agents: u8,
+    pub ticks_per_step: usize,
+    pub tick_seconds: f64,
+    pub max_steps: usize,
+    pub observation: ObservationConfig,
+    pub reward: RewardFn,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        EnvConfig {
+            agents: 1,
+            ticks_per_step: 10,
+            tick_seconds: constants::GAME_UPDATE_TICK_SECONDS,
+            max_steps: 1000,
+            observation: ObservationConfig::default(),
+            reward: survival_reward,
+        }
+    }
+}
+
+/// Rewards staying alive and penalizes crashing into planets.
+pub fn survival_reward(_space: &Space, _id: u8, crashed: bool) -> f64 {
+    if crashed {
+        -1.
+    } else {
+        0.01
+    }
+}
+
+/// Gym-style environment running a `Space` synchronously, without threads or sleeping.
+/// Agent `i` controls the ship with id `i + 1`.
+pub struct Env {
+    initial: Space,
+    space: Space,
+    config: EnvConfig,
+    steps: usize,
+}
+
+impl Env {
+    pub fn new(space: Space, config: EnvConfig) -> Env {
+        Env {
+            initial: space.clone(),
+            space,
+            config,
+            steps: 0,
+        }
+    }
+
+    pub fn space(&self) -> &Space {
+        &self.space
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+        self.space = self.initial.clone();
+        self.space.seed(seed);
+        self.steps = 0;
+        for id in self.ids() {
+            let location = self.space.gen_new_location();
+            self.space.add_ship(id, location);
+        }
+        self.observations()
+    }
+
+    /// Applies one thrust direction per agent (`None` to drift) and runs `ticks_per_step` ticks.
+    /// Ships under rotational control turn towards their direction and thrust once facing it.
+    pub fn step(&mut self, actions: &[Option<f64>]) -> Result<StepResult, Box<dyn Error>> {
+        if actions.len() != usize::from(self.config.agents) {
+            return Err(format!(
+                "expected {} actions, got {}",
+                self.config.agents,
+                actions.len()
+            )
+            .into());
+        }
+        if self.ids().any(|id| self.space.ship(id).is_none()) {
+            return Err("reset the environment before stepping it".into());
+        }
+        let deaths: Vec<u32> = self.ids().map(|id| self.deaths(id)).collect();
+        for _ in 0..self.config.ticks_per_step {
+            self.act(actions);
+            self.space.update(self.config.tick_seconds);
+        }
+        self.steps += 1;
+
+        let rewards = self
+            .ids()
+            .zip(deaths)
+            .map(|(id, deaths)| (self.config.reward)(&self.space, id, self.deaths(id) > deaths))
+            .collect();
+        Ok((
+            self.observations()?,
+            rewards,
+            self.steps >= self.config.max_steps,
+        ))
+    }
+
+    fn act(&mut self, actions: &[Option<f64>]) {
+        for (id, &direction) in self.ids().zip(actions) {
+            match self.space.ship_config().controls {
+                ControlModel::Direct => self.space.move_ship(id, direction),
+                ControlModel::Rotational {
+                    torque, max_spin, ..
+                } => {
+                    let heading = self.space.heading(id).unwrap_or_default();
+                    let spin = self.space.spin(id).unwrap_or_default();
+                    let steering =
+                        control::steer_towards(heading, spin, direction, torque, max_spin);
+                    self.space.steer_ship(id, steering);
+                }
+            }
+        }
+    }
+
+    pub fn observations(&self) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+        self.ids()
+            .map(|id| self.observe(id))
+            .collect::<Option<_>>()
+            .ok_or_else(|| "reset the environment before observing it".into())
+    }
+
+    /// Observation of the agent, `None` before its ship is placed.
+    fn observe(&self, id: u8) -> Option<Vec<f64>> {
+        let config = self.config.observation;
+        let ship = self.space.ship(id)?;
+        let mut observation = ship.location.to_array().to_vec();
+        if config.velocities {
+            observation.extend(ship.velocity.to_array());
+        }
+        if config.fields {
+            let field = self.space.field(id).unwrap_or(DVec2::ZERO);
+            observation.extend(field.to_array());
+        }
+        for planet in self.space.planets() {
+            if config.planet_positions {
+                observation.extend((planet.location - ship.location).to_array());
+            }
+            if config.velocities {
+                observation.extend((planet.velocity - ship.velocity).to_array());
+            }
+        }
+        Some(observation)
+    }
+
+    fn deaths(&self, id: u8) -> u32 {
+        self.space.deaths(id).unwrap_or_default()
+    }
+
+    fn ids(&self) -> impl Iterator<Item = u8> {
+        1..=self.config.agents
+    }
+}
+
+/// Independent environments stepped together, each on its own thread.
+pub struct VecEnv {
+    envs: Vec<Env>,
+}
+
+impl VecEnv {
+    pub fn new(space: Space, config: EnvConfig, count: usize) -> VecEnv {
+        VecEnv {
+            envs: (0..count)
+                .map(|_| Env::new(space.clone(), config))
+                .collect(),
+        }
+    }
+
+    pub fn envs(&self) -> &[Env] {
+        &self.envs
+    }
+
+    /// Resets environment `i` with `seed + i`.
+    pub fn reset(&mut self, seed: u64) -> Result<Vec<Vec<Vec<f64>>>, Box<dyn Error>> {
+        self.envs
+            .iter_mut()
+            .zip(seed..)
+            .map(|(env, seed)| env.reset(seed))
+            .collect()
+    }
+
+    pub fn step(
+        &mut self,
+        actions: &[Vec<Option<f64>>],
+    ) -> Result<Vec<StepResult>, Box<dyn Error>> {
+        if actions.len() != self.envs.len() {
+            return Err(format!(
+                "expected actions for {} environments, got {}",
+                self.envs.len(),
+                actions.len()
+            )
+            .into());
+        }
+        thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .envs
+                .iter_mut()
+                .zip(actions)
+                // errors are not Send, they cross the thread as text
+                .map(|(env, actions)| {
+                    scope.spawn(move || env.step(actions).map_err(|e| e.to_string()))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    let result = handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e));
+                    result.map_err(Into::into)
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::ShipConfig;
+
+    fn basic_env(config: EnvConfig) -> Env {
+        let ship_config = ShipConfig {
+            force: 0.1,
+            radius: 0.01,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        };
+        Env::new(Space::new(ship_config), config)
+    }
+
+    #[test]
+    fn reset_places_agent_ships() {
+        let mut env = basic_env(EnvConfig {
+            agents: 3,
+            ..Default::default()
+        });
+        let observations = env.reset(1).unwrap();
+        assert_eq!(3, observations.len());
+        assert_eq!(4, observations[0].len());
+        assert_eq!(3, env.space().ships().count());
+    }
+
+    #[test]
+    fn reset_same_seed_same_observations() {
+        let mut env = basic_env(EnvConfig::default());
+        assert_eq!(env.reset(7).unwrap(), env.reset(7).unwrap());
+        assert_ne!(env.reset(7).unwrap(), env.reset(8).unwrap());
+    }
+
+    #[test]
+    fn step_until_done() {
+        let mut env = basic_env(EnvConfig {
+            max_steps: 2,
+            ..Default::default()
+        });
+        env.reset(1).unwrap();
+        let (_, rewards, done) = env.step(&[Some(0.)]).unwrap();
+        assert_eq!(vec![0.01], rewards);
+        assert!(!done);
+        let (_, _, done) = env.step(&[None]).unwrap();
+        assert!(done);
+    }
+
+    #[test]
+    fn step_rejects_misuse() {
+        let mut env = basic_env(EnvConfig {
+            agents: 2,
+            ..Default::default()
+        });
+        assert!(env.observations().is_err());
+        assert!(env.step(&[None, None]).is_err());
+        env.reset(1).unwrap();
+        assert!(env.step(&[None]).is_err());
+        assert!(env.step(&[None, None, None]).is_err());
+        assert!(env.step(&[None, None]).is_ok());
+    }
+
+    #[test]
+    fn vec_env_steps_independent_spaces() {
+        let mut envs = VecEnv::new(
+            basic_env(EnvConfig::default()).space,
+            EnvConfig::default(),
+            4,
+        );
+        let observations = envs.reset(1).unwrap();
+        assert_eq!(4, observations.len());
+        let results = envs.step(&vec![vec![Some(0.)]; 4]).unwrap();
+        assert_eq!(4, results.len());
+        assert_ne!(results[0].0, results[1].0);
+        assert!(envs.step(&vec![vec![Some(0.)]; 3]).is_err());
+    }
+
+    #[test]
+    fn vec_env_matches_envs_stepped_one_at_a_time() {
+        let config = EnvConfig {
+            agents: 2,
+            ..Default::default()
+        };
+        let mut envs = VecEnv::new(basic_env(config).space, config, 3);
+        let mut singles: Vec<Env> = (0..3).map(|_| basic_env(config)).collect();
+        envs.reset(5).unwrap();
+        for (env, seed) in singles.iter_mut().zip(5..) {
+            env.reset(seed).unwrap();
+        }
+        let actions: Vec<Vec<Option<f64>>> =
+            (0..3).map(|i| vec![Some(f64::from(i)), None]).collect();
+        for _ in 0..5 {
+            let batch = envs.step(&actions).unwrap();
+            for ((env, actions), result) in singles.iter_mut().zip(&actions).zip(batch) {
+                assert_eq!(env.step(actions).unwrap(), result);
+            }
+        }
+    }
+
+    #[test]
+    fn rotational_ships_steer_towards_actions() {
+        let ship_config = ShipConfig {
+            controls: ControlModel::Rotational {
+                torque: 20.,
+                max_spin: 5.,
+                reverse: 0.,
+            },
+            ..basic_env(EnvConfig::default()).space.ship_config().clone()
+        };
+        let mut env = Env::new(Space::new(ship_config), EnvConfig::default());
+        env.reset(1).unwrap();
+        let start = env.space().ship(1).unwrap().location;
+        for _ in 0..100 {
+            env.step(&[Some(std::f64::consts::FRAC_PI_2)]).unwrap();
+        }
+        let heading = env.space().heading(1).unwrap();
+        assert!((heading - std::f64::consts::FRAC_PI_2).abs() < 0.05);
+        let moved = env.space().ship(1).unwrap().location - start;
+        assert!(moved.y > 2. * moved.x.abs());
+    }
+}
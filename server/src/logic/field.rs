@@ -0,0 +1,111 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How the field of a body falls off with the distance from its center.
+/// All laws reach `field / radius²` at the surface.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum FieldLaw {
+    /// `field / d²`, growing as `1 / d` inside of the body
+    #[default]
+    InverseSquare,
+    /// `field / (radius * d)`, reaching further than the inverse square
+    InverseLinear,
+    /// Smooth everywhere, `field * d / (d² + softening²)^1.5` scaled to the surface
+    Plummer { softening: f64 },
+    /// Inverse square screened by `exp(-(d - radius) / range)`
+    Yukawa { range: f64 },
+    /// Surface strength up to `range` from the center, nothing beyond
+    ConstantRange { range: f64 },
+}
+
+impl FieldLaw {
+    /// Strength at `distance` from the center of a body of `radius`.
+    pub fn strength(&self, field: f64, radius: f64, distance: f64) -> f64 {
+        let surface = field / radius.powi(2);
+        // linear from the center to the surface
+        let inside = surface * distance / radius;
+        match *self {
+            FieldLaw::InverseSquare => field * (distance.min(radius) / radius) / distance.powf(2.),
+            FieldLaw::InverseLinear if distance < radius => inside,
+            FieldLaw::InverseLinear => field / (radius * distance),
+            FieldLaw::Plummer { softening } => {
+                let plummer = |d: f64| d / (d.powi(2) + softening.powi(2)).powf(1.5);
+                surface * plummer(distance) / plummer(radius)
+            }
+            FieldLaw::Yukawa { .. } if distance < radius => inside,
+            FieldLaw::Yukawa { range } => {
+                field / distance.powi(2) * (-(distance - radius) / range).exp()
+            }
+            FieldLaw::ConstantRange { .. } if distance < radius => inside,
+            FieldLaw::ConstantRange { range } if distance <= range => surface,
+            FieldLaw::ConstantRange { .. } => 0.,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::{ShipConfig, Space};
+    use glam::DVec2;
+
+    const LAWS: [FieldLaw; 5] = [
+        FieldLaw::InverseSquare,
+        FieldLaw::InverseLinear,
+        FieldLaw::Plummer { softening: 0.05 },
+        FieldLaw::Yukawa { range: 0.2 },
+        FieldLaw::ConstantRange { range: 0.5 },
+    ];
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6 * b.abs().max(1.)
+    }
+
+    #[test]
+    fn laws_are_continuous_at_the_surface() {
+        for law in LAWS {
+            let surface = law.strength(2., 0.1, 0.1);
+            assert!(close(200., surface), "{law:?}");
+            assert!(close(surface, law.strength(2., 0.1, 0.1 - 1e-9)), "{law:?}");
+            assert!(close(surface, law.strength(2., 0.1, 0.1 + 1e-9)), "{law:?}");
+        }
+    }
+
+    #[test]
+    fn law_values() {
+        let strength = |law: FieldLaw, distance| law.strength(2., 0.1, distance);
+        assert!(close(2. / 0.16, strength(FieldLaw::InverseSquare, 0.4)));
+        assert!(close(2. / 0.04, strength(FieldLaw::InverseLinear, 0.4)));
+        assert!(close(100., strength(FieldLaw::InverseLinear, 0.05)));
+        let plummer = FieldLaw::Plummer { softening: 0.1 };
+        let scale = 200. / (0.1 / 0.02f64.powf(1.5));
+        assert!(close(
+            scale * 0.3 / 0.1f64.powf(1.5),
+            strength(plummer, 0.3)
+        ));
+        assert_eq!(0., strength(plummer, 0.));
+        let yukawa = FieldLaw::Yukawa { range: 0.2 };
+        assert!(close(2. / 0.09 * (-1f64).exp(), strength(yukawa, 0.3)));
+        let constant = FieldLaw::ConstantRange { range: 0.5 };
+        assert!(close(200., strength(constant, 0.5)));
+        assert_eq!(0., strength(constant, 0.6));
+    }
+
+    #[test]
+    fn planet_laws_reach_ships() {
+        let mut space = Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.01,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        });
+        space.add_planet(DVec2::new(0.5, 0.5), 1., 1., 0.05, DVec2::ZERO);
+        space.set_planet_field_law(0, FieldLaw::ConstantRange { range: 0.2 });
+        space.add_ship(1, DVec2::new(0.6, 0.5));
+        space.add_ship(2, DVec2::new(0.8, 0.5));
+        assert!(space.field(1).unwrap().x < 0.);
+        assert_eq!(Some(DVec2::ZERO), space.field(2));
+    }
+}
@@ -0,0 +1,211 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Energy pool of a ship shared by thrust, shields, mines and anchors.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FuelConfig {
+    pub capacity: f64,
+    /// Energy burnt per unit of force and second
+    pub burn_rate: f64,
+    /// Energy regained per second anywhere
+    #[serde(default)]
+    pub regeneration: f64,
+    /// Additional energy per second and unit of field strength, so ships refuel
+    /// faster close to planets
+    #[serde(default)]
+    pub planet_regeneration: f64,
+    /// Energy a shield spends on each crash it absorbs, it fails without it
+    #[serde(default)]
+    pub shield_cost: f64,
+    /// Energy spent on each mine or anchor dropped
+    #[serde(default)]
+    pub deploy_cost: f64,
+}
+
+impl FuelConfig {
+    /// Thrust that `energy` allows for `time` seconds and the energy left after it.
+    /// Without enough energy the thrust is cut down to what is left.
+    pub fn burn(&self, energy: f64, thrust: DVec2, time: f64) -> (DVec2, f64) {
+        let needed = self.burn_rate * thrust.length() * time;
+        if needed <= energy {
+            return (thrust, energy - needed);
+        }
+        (thrust * (energy.max(0.) / needed), 0.)
+    }
+
+    /// Energy left after spending `cost`, `None` if there is not enough.
+    pub fn spend(&self, energy: f64, cost: f64) -> Option<f64> {
+        (cost <= energy).then_some(energy - cost)
+    }
+
+    /// Energy after regaining energy for `time` seconds in a field of `field_strength`
+    /// with `bonus` regeneration from zones.
+    pub fn regenerate(&self, energy: f64, field_strength: f64, bonus: f64, time: f64) -> f64 {
+        let rate = self.regeneration + self.planet_regeneration * field_strength + bonus;
+        (energy + rate * time).min(self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{
+        deployable::{DeployKind, MineConfig},
+        motion::Motion,
+        object::Update,
+        pickup::PickupKind,
+        space::{ShipConfig, Space},
+        zone::{Effect, Shape, Zone},
+    };
+
+    fn space(fuel: FuelConfig, mines: Option<MineConfig>) -> Space {
+        Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            fuel: Some(fuel),
+            mines,
+            ..Default::default()
+        })
+    }
+
+    fn config() -> FuelConfig {
+        FuelConfig {
+            capacity: 10.,
+            burn_rate: 2.,
+            regeneration: 1.,
+            planet_regeneration: 0.5,
+            shield_cost: 4.,
+            deploy_cost: 1.,
+        }
+    }
+
+    #[test]
+    fn thrust_is_limited_by_energy() {
+        let thrust = DVec2::new(3., 4.);
+        assert_eq!((thrust, 5.), config().burn(10., thrust, 0.5));
+        assert_eq!((thrust * 0.5, 0.), config().burn(2.5, thrust, 0.5));
+        assert_eq!((DVec2::ZERO, 0.), config().burn(0., thrust, 0.5));
+        assert_eq!((DVec2::ZERO, 3.), config().burn(3., DVec2::ZERO, 0.5));
+    }
+
+    #[test]
+    fn energy_regenerates_up_to_capacity() {
+        assert_eq!(2., config().regenerate(1., 0., 0., 1.));
+        assert_eq!(4.5, config().regenerate(1., 3., 1., 1.));
+        assert_eq!(10., config().regenerate(9., 0., 0., 5.));
+    }
+
+    #[test]
+    fn spending_needs_enough_energy() {
+        assert_eq!(Some(1.), config().spend(5., 4.));
+        assert_eq!(Some(0.), config().spend(4., 4.));
+        assert_eq!(None, config().spend(3., 4.));
+    }
+
+    #[test]
+    fn thrust_stops_without_fuel() {
+        let mut space = space(
+            FuelConfig {
+                capacity: 1.,
+                burn_rate: 10.,
+                regeneration: 0.,
+                planet_regeneration: 0.,
+                shield_cost: 0.,
+                deploy_cost: 0.,
+            },
+            None,
+        );
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.move_ship(1, Some(0.));
+        space.update(0.5);
+        assert_eq!(Some((0.5, 1.)), space.fuel(1));
+        space.update(1.);
+        let speed = space.ship(1).unwrap().velocity.x;
+        assert_eq!(Some((0., 1.)), space.fuel(1));
+        space.update(0.1);
+        assert_eq!(speed, space.ship(1).unwrap().velocity.x);
+    }
+
+    #[test]
+    fn shields_and_deployables_share_the_energy() {
+        let mut space = space(
+            FuelConfig {
+                capacity: 10.,
+                burn_rate: 0.,
+                regeneration: 0.,
+                planet_regeneration: 0.,
+                shield_cost: 2.,
+                deploy_cost: 4.,
+            },
+            Some(MineConfig {
+                max: 5,
+                radius: 0.01,
+                trigger: 0.01,
+                arm_seconds: 10.,
+            }),
+        );
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        for _ in 0..3 {
+            space.deploy(1, DeployKind::Mine);
+        }
+        assert_eq!(2, space.deployables().count());
+        assert_eq!(Some((2., 10.)), space.fuel(1));
+
+        space
+            .ship_mut(1)
+            .unwrap()
+            .collect(PickupKind::Shield { seconds: 10. });
+        space.add_planet(DVec2::new(0.5, 0.5), 0., 0., 0.05, DVec2::ZERO);
+        space.update(0.01);
+        assert_eq!(Some(0), space.deaths(1));
+        assert_eq!(Some((0., 10.)), space.fuel(1));
+        // the shield is still up but out of energy
+        space.ship_mut(1).unwrap().object_mut().location = DVec2::new(0.5, 0.5);
+        space.update(0.01);
+        assert_eq!(Some(1), space.deaths(1));
+    }
+
+    #[test]
+    fn fuel_regenerates_near_planets_and_in_refuel_zones() {
+        let mut space = space(
+            FuelConfig {
+                capacity: 10.,
+                burn_rate: 1.,
+                regeneration: 0.,
+                planet_regeneration: 1.,
+                shield_cost: 0.,
+                deploy_cost: 0.,
+            },
+            None,
+        );
+        space.add_planet(DVec2::new(0.2, 0.5), 0., 0.1, 0.05, DVec2::ZERO);
+        space.set_planet_motion(0, Motion::Anchored);
+        space.set_zones(vec![Zone {
+            name: None,
+            shape: Shape::Circle {
+                center: DVec2::new(0.8, 0.8),
+                radius: 0.1,
+            },
+            effects: vec![Effect::Refuel { rate: 1. }],
+        }]);
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.add_ship(2, DVec2::new(0.8, 0.2));
+        space.add_ship(3, DVec2::new(0.8, 0.8));
+        for id in 1..=3 {
+            let ship = space.ship_mut(id).unwrap();
+            ship.burn(DVec2::new(5., 0.), 1.);
+            ship.object_mut().mass = 0.;
+            assert_eq!(Some((5., 10.)), ship.fuel_gauge(), "{id}");
+        }
+        space.update(1.);
+        let energy = |id| space.fuel(id).unwrap().0;
+        // closer to the planet regenerates faster
+        assert!(energy(1) > energy(2));
+        assert!(energy(2) > 5.);
+        assert!(energy(3) >= 6.);
+    }
+}
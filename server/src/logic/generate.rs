@@ -0,0 +1,344 @@
+use glam::DVec2;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::TAU;
+use std::fmt;
+use std::str::FromStr;
+
+use super::field::FieldLaw;
+use super::map::{default_ship_config, Map, Metadata, PlanetConfig, PlanetKind, MAP_VERSION};
+use super::motion::Motion;
+use super::orbit::Orbit;
+use super::spawn::SpawnConfig;
+use super::validate::{validate, MapErrors};
+
+const PLACEMENT_ATTEMPTS: usize = 1000;
+
+/// Values sampled uniformly between `min` and `max`, written `min..max` or as a single value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Interval {
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        if self.min >= self.max {
+            return self.min;
+        }
+        rng.gen_range(self.min..=self.max)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.min, self.max)
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number '{value}'"))
+        };
+        let (min, max) = match s.split_once("..") {
+            Some((min, max)) => (parse(min)?, parse(max)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if !(min.is_finite() && max.is_finite()) || min > max {
+            return Err(format!("invalid interval '{s}'"));
+        }
+        Ok(Interval { min, max })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    pub planets: usize,
+    pub mass: Interval,
+    pub radius: Interval,
+    pub field: Interval,
+    /// Minimal free distance between bodies, orbits and the world border
+    pub separation: f64,
+    /// Moons on circular orbits, spread over the planets
+    pub moons: usize,
+    pub moon_radius: f64,
+    pub moon_mass: f64,
+    /// Rings of asteroids orbiting planets outside of their moons
+    pub belts: usize,
+    pub belt_asteroids: usize,
+    pub asteroid_radius: f64,
+    pub asteroid_mass: f64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            seed: 0,
+            planets: 4,
+            mass: Interval {
+                min: 10.,
+                max: 1000.,
+            },
+            radius: Interval {
+                min: 0.015,
+                max: 0.04,
+            },
+            field: Interval {
+                min: 0.05,
+                max: 0.2,
+            },
+            separation: 0.05,
+            moons: 0,
+            moon_radius: 0.006,
+            moon_mass: 1.,
+            belts: 0,
+            belt_asteroids: 16,
+            asteroid_radius: 0.003,
+            asteroid_mass: 1.,
+        }
+    }
+}
+
+/// Bodies circling a planet at one distance.
+struct Ring {
+    distance: f64,
+    count: usize,
+    radius: f64,
+    mass: f64,
+}
+
+/// Planet with its moons and belts, kept apart from other systems as a whole.
+struct System {
+    location: DVec2,
+    radius: f64,
+    mass: f64,
+    field: f64,
+    rings: Vec<Ring>,
+}
+
+impl System {
+    /// Adds a ring of `count` bodies outside of the existing ones.
+    fn add_ring(&mut self, count: usize, radius: f64, mass: f64, separation: f64) {
+        // keep neighbours on the ring apart
+        let min_distance = count as f64 * (2. * radius + separation) / TAU;
+        let distance = (self.extent() + separation + radius).max(min_distance);
+        self.rings.push(Ring {
+            distance,
+            count,
+            radius,
+            mass,
+        });
+    }
+
+    /// Distance from the planet center that the system occupies.
+    fn extent(&self) -> f64 {
+        self.rings
+            .last()
+            .map_or(self.radius, |ring| ring.distance + ring.radius)
+    }
+
+    fn bodies(&self, name: String, rng: &mut impl Rng) -> Vec<PlanetConfig> {
+        let mut bodies = vec![PlanetConfig {
+            name: Some(name.clone()),
+            location: Some(self.location),
+            radius: self.radius,
+            mass: self.mass,
+            field: self.field,
+            velocity: DVec2::ZERO,
+            orbit: None,
+            motion: Motion::Dynamic,
+            kind: PlanetKind::Planet,
+            field_law: None,
+        }];
+        for ring in self.rings.iter() {
+            let phase = rng.gen_range(0. ..TAU);
+            for index in 0..ring.count {
+                bodies.push(PlanetConfig {
+                    name: None,
+                    location: None,
+                    radius: ring.radius,
+                    mass: ring.mass,
+                    field: 0.,
+                    velocity: DVec2::ZERO,
+                    orbit: Some(Orbit {
+                        parent: name.clone(),
+                        semi_major_axis: ring.distance,
+                        eccentricity: 0.,
+                        periapsis: 0.,
+                        phase: phase + TAU * index as f64 / ring.count as f64,
+                        retrograde: false,
+                    }),
+                    motion: Motion::Dynamic,
+                    kind: PlanetKind::Planet,
+                    field_law: None,
+                });
+            }
+        }
+        bodies
+    }
+}
+
+/// Builds a map from `config`, the same seed always gives the same map.
+pub fn generate(config: &GeneratorConfig) -> Result<Map, Box<dyn std::error::Error>> {
+    if (config.moons > 0 || config.belts > 0) && config.planets == 0 {
+        return Err("moons and belts need planets to orbit".into());
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+
+    let mut systems: Vec<System> = (0..config.planets)
+        .map(|_| System {
+            location: DVec2::ZERO,
+            radius: config.radius.sample(&mut rng),
+            mass: config.mass.sample(&mut rng),
+            field: config.field.sample(&mut rng),
+            rings: vec![],
+        })
+        .collect();
+    for _ in 0..config.moons {
+        let index = rng.gen_range(0..systems.len());
+        systems[index].add_ring(1, config.moon_radius, config.moon_mass, config.separation);
+    }
+    for _ in 0..config.belts {
+        let index = rng.gen_range(0..systems.len());
+        systems[index].add_ring(
+            config.belt_asteroids,
+            config.asteroid_radius,
+            config.asteroid_mass,
+            config.separation,
+        );
+    }
+
+    // place the largest systems first
+    systems.sort_by(|a, b| b.extent().total_cmp(&a.extent()));
+    for index in 0..systems.len() {
+        let extent = systems[index].extent();
+        let border = extent + config.separation;
+        if border >= 0.5 {
+            return Err(format!("planet {index} with its orbits does not fit the world").into());
+        }
+        let placed = (0..PLACEMENT_ATTEMPTS).find_map(|_| {
+            let location = DVec2::new(
+                rng.gen_range(border..=1. - border),
+                rng.gen_range(border..=1. - border),
+            );
+            systems[..index]
+                .iter()
+                .all(|other| {
+                    location.distance(other.location) >= extent + other.extent() + config.separation
+                })
+                .then_some(location)
+        });
+        match placed {
+            Some(location) => systems[index].location = location,
+            None => {
+                return Err(format!(
+                    "no room for planet {index}, use fewer or smaller bodies or less separation"
+                )
+                .into())
+            }
+        }
+    }
+
+    let map = Map {
+        schema: None,
+        version: MAP_VERSION,
+        metadata: Metadata {
+            name: Some(format!("Generated {}", config.seed)),
+            description: Some(format!(
+                "planets: {}, moons: {}, asteroid belts: {}",
+                config.planets, config.moons, config.belts
+            )),
+            author: None,
+            recommended_players: None,
+        },
+        ship_config: default_ship_config(),
+        field_law: FieldLaw::default(),
+        planets: systems
+            .iter()
+            .enumerate()
+            .flat_map(|(index, system)| system.bodies(format!("planet {index}"), &mut rng))
+            .collect(),
+        spawn: SpawnConfig::default(),
+        spawn_points: vec![],
+        seed: Some(config.seed),
+        zones: vec![],
+        walls: vec![],
+        pickups: vec![],
+    };
+    let errors = validate(&map);
+    if !errors.is_empty() {
+        return Err(MapErrors(errors).into());
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(seed: u64) -> GeneratorConfig {
+        GeneratorConfig {
+            seed,
+            moons: 3,
+            belts: 1,
+            ..GeneratorConfig::default()
+        }
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        let map = serde_json::to_string(&generate(&config(7)).unwrap()).unwrap();
+        let same = serde_json::to_string(&generate(&config(7)).unwrap()).unwrap();
+        let other = serde_json::to_string(&generate(&config(8)).unwrap()).unwrap();
+        assert_eq!(map, same);
+        assert_ne!(map, other);
+    }
+
+    #[test]
+    fn generated_maps_are_valid() {
+        for seed in 0..20 {
+            let map = generate(&config(seed)).unwrap();
+            assert_eq!(4 + 3 + 16, map.planets.len());
+        }
+        let crowded = GeneratorConfig {
+            planets: 100,
+            ..GeneratorConfig::default()
+        };
+        assert!(generate(&crowded).is_err());
+    }
+
+    #[test]
+    fn moons_stay_in_orbit() {
+        let map = generate(&GeneratorConfig {
+            planets: 1,
+            moons: 1,
+            ..GeneratorConfig::default()
+        })
+        .unwrap();
+        let mut space = map.into_space();
+        let distance = |space: &crate::Space| {
+            let bodies: Vec<_> = space.planets().collect();
+            bodies[0].location.distance(bodies[1].location)
+        };
+        let start = distance(&space);
+        for _ in 0..5000 {
+            space.update(0.001);
+            assert!((distance(&space) - start).abs() < 0.1 * start);
+        }
+    }
+
+    #[test]
+    fn parse_intervals() {
+        assert_eq!(Ok(Interval { min: 1., max: 2. }), "1..2".parse());
+        assert_eq!(Ok(Interval { min: 3., max: 3. }), "3".parse());
+        assert!("2..1".parse::<Interval>().is_err());
+        assert!("a..1".parse::<Interval>().is_err());
+    }
+}
@@ -0,0 +1,709 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::constants;
+use crate::ui::admin::AdminCommand;
+use crate::ui::command::Command;
+
+use super::body::BodyKind;
+use super::field::FieldLaw;
+use super::motion::Motion;
+use super::object::Object;
+use super::orbit::Orbit;
+use super::pickup::Spawner;
+use super::space::{ShipConfig, Space};
+use super::spawn::{SpawnArea, SpawnConfig, SpawnPoint};
+use super::validate::{validate, MapError, MapErrors};
+use super::wall::Wall;
+use super::zone::Zone;
+
+pub const MAP_VERSION: u32 = 2;
+
+/// Map file content, published as `schema/map.schema.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Map {
+    /// Schema editors check the file against
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Map format version, files without it are version 1 serialized spaces
+    pub version: u32,
+    #[serde(default)]
+    pub metadata: Metadata,
+    #[serde(default = "default_ship_config")]
+    pub ship_config: ShipConfig,
+    /// Field law of planets that do not choose their own
+    #[serde(default)]
+    pub field_law: FieldLaw,
+    #[serde(default)]
+    pub planets: Vec<PlanetConfig>,
+    #[serde(default)]
+    pub spawn: SpawnConfig,
+    /// Named locations ships spawn at, in addition to the spawn areas
+    #[serde(default)]
+    pub spawn_points: Vec<SpawnPoint>,
+    /// Seed for all randomness, random if unset
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Areas with drag, currents, thrust dampening or sensor blackout
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    /// Segments and convex polygons ships bounce off or crash into
+    #[serde(default)]
+    pub walls: Vec<Wall>,
+    /// Power-ups placed on the map on timers
+    #[serde(default)]
+    pub pickups: Vec<Spawner>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Metadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub recommended_players: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PlanetConfig {
+    /// Name for other planets to orbit this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Required unless the planet has an orbit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<[f64; 2]>")]
+    pub location: Option<DVec2>,
+    pub radius: f64,
+    pub mass: f64,
+    pub field: f64,
+    #[serde(default)]
+    #[schemars(with = "[f64; 2]")]
+    pub velocity: DVec2,
+    /// Orbit around another planet, replacing location and velocity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orbit: Option<Orbit>,
+    /// Planets on a path need no location
+    #[serde(default, skip_serializing_if = "Motion::is_dynamic")]
+    pub motion: Motion,
+    #[serde(default, skip_serializing_if = "PlanetKind::is_planet")]
+    pub kind: PlanetKind,
+    /// Overrides the field law of the map
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_law: Option<FieldLaw>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum PlanetKind {
+    #[default]
+    Planet,
+    /// Destroys ships closer than `horizon` to its center
+    BlackHole { horizon: f64 },
+    /// Pushes everything away with its field
+    Repulsor,
+    /// Moves ships and planets entering it out of the wormhole named `exit`
+    Wormhole { exit: String },
+}
+
+impl PlanetKind {
+    pub fn is_planet(&self) -> bool {
+        *self == PlanetKind::Planet
+    }
+}
+
+impl PlanetConfig {
+    fn object(&self, location: DVec2, velocity: DVec2, map_law: FieldLaw) -> Object {
+        Object {
+            law: self.field_law.unwrap_or(map_law),
+            ..Object::new(location, self.radius, self.mass, self.field, velocity)
+        }
+    }
+}
+
+pub fn default_ship_config() -> ShipConfig {
+    ShipConfig {
+        force: 2000.,
+        radius: 0.01,
+        mass: 100.,
+        field: 1.,
+        ..Default::default()
+    }
+}
+
+impl Map {
+    /// Index of the planet called `name` among the first `count` planets.
+    pub fn find_planet(&self, name: &str, count: usize) -> Option<usize> {
+        self.planets[..count]
+            .iter()
+            .position(|planet| planet.name.as_deref() == Some(name))
+    }
+
+    /// Initial location and velocity of every planet, with orbits resolved.
+    /// Planets missing a location or parent start at the origin.
+    pub fn planet_states(&self) -> Vec<(DVec2, DVec2)> {
+        let mut states: Vec<(DVec2, DVec2)> = vec![];
+        for (index, planet) in self.planets.iter().enumerate() {
+            let orbit = planet.orbit.as_ref().and_then(|orbit| {
+                let parent_index = self.find_planet(&orbit.parent, index)?;
+                let (location, velocity) = states[parent_index];
+                let parent = self.planets[parent_index].object(location, velocity, self.field_law);
+                let (offset, orbit_velocity) =
+                    orbit.state(&parent, &planet.object(location, velocity, self.field_law));
+                Some((location + offset, velocity + orbit_velocity))
+            });
+            states.push(orbit.unwrap_or((planet.location.unwrap_or_default(), planet.velocity)));
+        }
+        states
+    }
+
+    pub fn into_space(self) -> Space {
+        let mut space = Space::new(self.ship_config.clone());
+        for (index, (planet, (location, velocity))) in
+            self.planets.iter().zip(self.planet_states()).enumerate()
+        {
+            space.add_planet(location, planet.mass, planet.field, planet.radius, velocity);
+            space.set_planet_motion(index, planet.motion.clone());
+            let kind = match &planet.kind {
+                PlanetKind::Planet => BodyKind::Planet,
+                &PlanetKind::BlackHole { horizon } => BodyKind::BlackHole { horizon },
+                PlanetKind::Repulsor => BodyKind::Repulsor,
+                PlanetKind::Wormhole { exit } => match self.find_planet(exit, self.planets.len()) {
+                    Some(exit) => BodyKind::Wormhole { exit },
+                    None => BodyKind::Planet,
+                },
+            };
+            space.set_planet_kind(index, kind);
+            space.set_planet_field_law(index, planet.field_law.unwrap_or(self.field_law));
+        }
+        let mut spawn = self.spawn;
+        spawn.areas.extend(
+            self.spawn_points
+                .iter()
+                .map(|point| SpawnArea::Point(point.location)),
+        );
+        space.set_spawn_config(spawn);
+        space.set_spawn_points(self.spawn_points);
+        space.set_zones(self.zones);
+        space.set_walls(self.walls);
+        space.set_spawners(self.pickups);
+        if let Some(seed) = self.seed {
+            space.seed(seed);
+        }
+        space
+    }
+}
+
+/// JSON Schema of the current map format.
+pub fn schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(Map)).unwrap()
+}
+
+fn map_error(path: &str, message: impl Into<String>) -> MapErrors {
+    MapErrors(vec![MapError {
+        path: path.to_string(),
+        message: message.into(),
+    }])
+}
+
+/// Brings a map of an older version to the current layout.
+fn migrate(mut value: Value) -> Result<Value, MapErrors> {
+    let version = match value.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| map_error("$.version", "must be a positive integer"))?,
+    };
+    if version == 0 || version > u64::from(MAP_VERSION) {
+        return Err(map_error(
+            "$.version",
+            format!("{version} is not a known version, the latest is {MAP_VERSION}"),
+        ));
+    }
+    if version == 1 {
+        // planets were wrapped in objects, their acceleration and the ships are dropped
+        if let Some(planets) = value.get_mut("planets").and_then(Value::as_array_mut) {
+            for planet in planets {
+                if let Some(mut object) = planet.get_mut("object").map(Value::take) {
+                    if let Some(object) = object.as_object_mut() {
+                        object.remove("acceleration");
+                    }
+                    *planet = object;
+                }
+            }
+        }
+        if let Some(map) = value.as_object_mut() {
+            map.remove("ships");
+            map.insert(String::from("version"), Value::from(2));
+        }
+    }
+    Ok(value)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    Json,
+    Toml,
+    Ron,
+}
+
+impl MapFormat {
+    pub fn from_path(path: &Path) -> Option<MapFormat> {
+        match path.extension()?.to_str()? {
+            "json" => Some(MapFormat::Json),
+            "toml" => Some(MapFormat::Toml),
+            "ron" => Some(MapFormat::Ron),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format of a file without a known extension from its first character.
+    fn sniff(text: &str) -> MapFormat {
+        match text.trim_start().chars().next() {
+            Some('{') => MapFormat::Json,
+            Some('(') => MapFormat::Ron,
+            _ => MapFormat::Toml,
+        }
+    }
+}
+
+/// Lets optional values be written without `Some(...)`.
+fn ron_options() -> ron::Options {
+    ron::Options::default().with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+}
+
+fn deserialize<'de, D>(deserializer: D) -> Result<Map, MapErrors>
+where
+    D: serde::Deserializer<'de>,
+    D::Error: std::fmt::Display,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = match e.path().to_string().as_str() {
+            "." => String::from("$"),
+            path => format!("$.{path}"),
+        };
+        map_error(&path, e.inner().to_string())
+    })
+}
+
+/// Parses a map in any format, only JSON maps can be of an older version.
+pub fn parse(text: &str, format: MapFormat) -> Result<Map, Box<dyn std::error::Error>> {
+    let map = match format {
+        MapFormat::Json => deserialize(migrate(serde_json::from_str(text)?)?)?,
+        MapFormat::Toml => deserialize(toml::Deserializer::new(text))?,
+        MapFormat::Ron => deserialize(&mut ron::Deserializer::from_str_with_options(
+            text,
+            ron_options(),
+        )?)?,
+    };
+    if map.version != MAP_VERSION {
+        return Err(map_error(
+            "$.version",
+            format!("{} is not the supported version {MAP_VERSION}", map.version),
+        )
+        .into());
+    }
+    let errors = validate(&map);
+    if !errors.is_empty() {
+        return Err(MapErrors(errors).into());
+    }
+    Ok(map)
+}
+
+/// Reads a map in the format given by its extension, or guessed from its content,
+/// and validates it. Errors point to the offending values.
+pub fn read(path: &Path) -> Result<Map, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)?;
+    let format = MapFormat::from_path(path).unwrap_or_else(|| MapFormat::sniff(&text));
+    parse(&text, format)
+}
+
+pub fn load(path: &Path) -> Result<Space, Box<dyn std::error::Error>> {
+    Ok(read(path)?.into_space())
+}
+
+/// Writes a map in the format given by the extension of `path`.
+pub fn write(map: &Map, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    // the schema reference only means something to editors of JSON files
+    let without_schema = || Map {
+        schema: None,
+        ..map.clone()
+    };
+    let text = match MapFormat::from_path(path) {
+        Some(MapFormat::Json) => serde_json::to_string_pretty(map)?,
+        Some(MapFormat::Toml) => toml::to_string(&without_schema())?,
+        Some(MapFormat::Ron) => {
+            ron_options().to_string_pretty(&without_schema(), ron::ser::PrettyConfig::default())?
+        }
+        None => return Err(format!("unknown map format of {}", path.display()).into()),
+    };
+    fs::write(path, text + "\n")?;
+    Ok(())
+}
+
+fn is_map(path: &Path) -> bool {
+    MapFormat::from_path(path).is_some()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Expands directories to the map files they contain, sorted by name.
+pub fn map_paths(paths: &[String]) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut maps = vec![];
+    for path in paths {
+        let path = PathBuf::from(path);
+        if path.is_dir() {
+            let mut dir_maps = vec![];
+            for entry in fs::read_dir(&path)? {
+                let entry_path = entry?.path();
+                if is_map(&entry_path) {
+                    dir_maps.push(entry_path);
+                }
+            }
+            dir_maps.sort();
+            maps.append(&mut dir_maps);
+        } else {
+            maps.push(path);
+        }
+    }
+    if maps.is_empty() {
+        return Err("no maps found".into());
+    }
+    Ok(maps)
+}
+
+/// Maps played one after another, remembering which map file is in play and since when.
+pub struct MapRotation {
+    paths: Vec<PathBuf>,
+    current: usize,
+    /// Map file in play, unset after resuming a checkpoint
+    playing: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    /// Tick the match on the map in play started at
+    match_start: u64,
+}
+
+impl MapRotation {
+    pub fn new(paths: Vec<PathBuf>) -> MapRotation {
+        let modified = modified(&paths[0]);
+        MapRotation {
+            playing: Some(paths[0].clone()),
+            paths,
+            current: 0,
+            modified,
+            match_start: 0,
+        }
+    }
+
+    /// Position in the rotation.
+    pub fn current(&self) -> &Path {
+        &self.paths[self.current]
+    }
+
+    pub fn playing(&self) -> Option<&Path> {
+        self.playing.as_deref()
+    }
+
+    /// Starts a match on a map file, which need not be part of the rotation.
+    pub fn play(&mut self, path: &Path, tick: u64) {
+        self.playing = Some(path.to_path_buf());
+        self.modified = modified(path);
+        self.match_start = tick;
+    }
+
+    /// Continues a game resumed from a checkpoint, which has no map file to reload.
+    pub fn resume(&mut self, tick: u64) {
+        self.playing = None;
+        self.match_start = tick;
+    }
+
+    /// Starts a match on the next map of the rotation.
+    pub fn advance(&mut self, tick: u64) -> &Path {
+        self.current = (self.current + 1) % self.paths.len();
+        let path = self.paths[self.current].clone();
+        self.play(&path, tick);
+        self.current()
+    }
+
+    /// Returns whether the map file in play changed since the last check.
+    pub fn changed(&mut self) -> bool {
+        let Some(playing) = &self.playing else {
+            return false;
+        };
+        let modified = modified(playing);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+
+    /// Whether the match has lasted `match_seconds` of game time at `tick`.
+    pub fn match_over(&self, tick: u64, match_seconds: Option<f64>) -> bool {
+        let played = tick.saturating_sub(self.match_start) as f64;
+        match_seconds.is_some_and(|seconds| played * constants::GAME_UPDATE_TICK_SECONDS >= seconds)
+    }
+}
+
+/// Switches to the next map once a match has lasted `match_seconds` of game time and
+/// reloads the map in play when its file changes. Ships keep their ids and are
+/// respawned on the new map.
+pub fn run_maps(
+    command_sender: &Sender<Command>,
+    rotation: &Arc<Mutex<MapRotation>>,
+    space_counter: &Arc<Mutex<Space>>,
+    match_seconds: Option<f64>,
+) {
+    loop {
+        std::thread::sleep(Duration::from_secs_f64(constants::MAP_WATCH_SECONDS));
+        let tick = space_counter.lock().unwrap().get_tick();
+        let mut rotation = rotation.lock().unwrap();
+        let path = if rotation.match_over(tick, match_seconds) {
+            rotation.advance(tick).to_path_buf()
+        } else if rotation.changed() {
+            rotation.playing().unwrap().to_path_buf()
+        } else {
+            continue;
+        };
+        // a broken map keeps the previous one running
+        match load(&path) {
+            Ok(map) => {
+                println!("Loading map {}", path.display());
+                let command = Command::Admin(AdminCommand::LoadMap(Box::new(map)));
+                if command_sender.send(command).is_err() {
+                    return;
+                }
+            }
+            Err(e) => eprintln!("Map error in {}: {e}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        for file in ["b.json", "a.json", "notes.txt"] {
+            fs::write(dir.join(file), "{}").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn example_map_is_valid() {
+        let map = read(Path::new("../maps/example.json")).unwrap();
+        assert_eq!(Some("Example"), map.metadata.name.as_deref());
+    }
+
+    #[test]
+    fn migrate_version_1_maps() {
+        let value = serde_json::json!({
+            "ship_config": {"force": 1.0, "radius": 0.01, "mass": 1.0, "field": 1.0},
+            "planets": [{"object": {
+                "location": [0.5, 0.5], "radius": 0.1, "mass": 1.0, "field": 0.1,
+                "velocity": [0.0, 0.1], "acceleration": [0.0, 0.0]
+            }}],
+            "ships": []
+        });
+        let map: Map = serde_json::from_value(migrate(value).unwrap()).unwrap();
+        assert_eq!(MAP_VERSION, map.version);
+        assert_eq!(DVec2::new(0., 0.1), map.planets[0].velocity);
+        assert!(migrate(serde_json::json!({ "version": 3 })).is_err());
+        assert!(migrate(serde_json::json!({ "version": 0 })).is_err());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        for text in [
+            r#"{"version": 2, "seeed": 3}"#,
+            r#"{"version": 2, "ship_config": {"force": 1.0, "radius": 0.01, "mass": 1.0, "field": 1.0, "fuel": {"capacity": 1.0, "burn_rate": 1.0}, "colour": 1}}"#,
+            r#"{"version": 2, "planets": [{"location": [0.5, 0.5], "radius": 0.1, "mass": 1.0, "field": 0.1, "kind": {"black_hole": {"horizon": 0.05, "horizn": 0.1}}}]}"#,
+        ] {
+            assert!(parse(text, MapFormat::Json).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn spawn_points_are_spawn_locations() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "spawn_points": [{"name": "center", "location": [0.5, 0.5]}]
+        }))
+        .unwrap();
+        let mut space = map.into_space();
+        assert_eq!(DVec2::new(0.5, 0.5), space.gen_new_location());
+        assert_eq!("center", space.spawn_points()[0].name);
+    }
+
+    #[test]
+    fn convert_between_formats() {
+        let map = read(Path::new("../maps/example.json")).unwrap();
+        for file in ["space_game_map_test.toml", "space_game_map_test.ron"] {
+            let path = std::env::temp_dir().join(file);
+            write(&map, &path).unwrap();
+            let converted = read(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_eq!(
+                map.clone().into_space().get_state_binary(),
+                converted.into_space().get_state_binary()
+            );
+        }
+    }
+
+    #[test]
+    fn sniff_format_without_extension() {
+        assert_eq!(MapFormat::Json, MapFormat::sniff(" {}"));
+        assert_eq!(MapFormat::Ron, MapFormat::sniff("(version: 2)"));
+        assert_eq!(MapFormat::Toml, MapFormat::sniff("version = 2"));
+        let map = parse("version = 2\n[[planets]]\nlocation = [0.5, 0.5]\nradius = 0.1\nmass = 1.0\nfield = 0.1\n", MapFormat::Toml).unwrap();
+        assert_eq!(1, map.planets.len());
+        let error = parse(
+            "(version: 2, planets: [(location: (0.5, 0.5), radius: \"big\")])",
+            MapFormat::Ron,
+        )
+        .unwrap_err();
+        assert!(error.to_string().starts_with("$.planets[0].radius"));
+    }
+
+    #[test]
+    fn orbits_stay_bound() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "planets": [
+                {"name": "sun", "location": [0.5, 0.5], "radius": 0.05, "mass": 1e6, "field": 0.1},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "sun", "semi_major_axis": 0.15, "phase": 2.0}},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "sun", "semi_major_axis": 0.3, "eccentricity": 0.3,
+                        "periapsis": 1.0, "retrograde": true}},
+            ]
+        }))
+        .unwrap();
+        assert!(validate(&map).is_empty());
+        let mut space = map.into_space();
+        let distances = |space: &Space| -> Vec<f64> {
+            let bodies: Vec<_> = space.planets().collect();
+            bodies[1..]
+                .iter()
+                .map(|body| body.location.distance(bodies[0].location))
+                .collect()
+        };
+        for _ in 0..20000 {
+            space.update(0.001);
+            let distances = distances(&space);
+            assert!((distances[0] - 0.15).abs() < 0.01);
+            assert!((0.2..0.4).contains(&distances[1]));
+        }
+    }
+
+    #[test]
+    fn published_schema_is_up_to_date() {
+        let published = fs::read_to_string("../schema/map.schema.json").unwrap();
+        assert_eq!(schema(), published.trim_end());
+    }
+
+    #[test]
+    fn load_reports_json_path() {
+        let path = std::env::temp_dir().join("space_game_invalid_map_test.json");
+        let data = std::fs::read_to_string("../maps/example.json").unwrap();
+        fs::write(&path, data.replacen("0.02", "\"big\"", 1)).unwrap();
+        let error = load(&path).unwrap_err().to_string();
+        fs::write(&path, data.replacen("0.02", "-0.02", 1)).unwrap();
+        let invalid = load(&path).unwrap_err().to_string();
+        fs::remove_file(&path).unwrap();
+        assert!(error.starts_with("$.planets[0].radius: invalid type"));
+        assert!(invalid.starts_with("$.planets[0].radius: must be positive"));
+    }
+
+    #[test]
+    fn directory_maps_are_sorted() {
+        let dir = map_dir("space_game_map_paths_test");
+        let paths = map_paths(&[
+            String::from("first.json"),
+            dir.to_str().unwrap().to_string(),
+        ])
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            vec![
+                PathBuf::from("first.json"),
+                dir.join("a.json"),
+                dir.join("b.json")
+            ],
+            paths
+        );
+        assert!(map_paths(&[]).is_err());
+    }
+
+    #[test]
+    fn rotation_wraps_and_detects_changes() {
+        let dir = map_dir("space_game_map_rotation_test");
+        let mut rotation = MapRotation::new(vec![dir.join("a.json"), dir.join("b.json")]);
+        assert!(!rotation.changed());
+        fs::File::options()
+            .write(true)
+            .open(dir.join("a.json"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(rotation.changed());
+        assert!(!rotation.changed());
+        assert_eq!(dir.join("b.json"), rotation.advance(0));
+        assert_eq!(dir.join("a.json"), rotation.advance(0));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotation_follows_the_map_in_play() {
+        let dir = map_dir("space_game_map_playing_test");
+        let mut rotation = MapRotation::new(vec![dir.join("a.json"), dir.join("b.json")]);
+        let touch = |file: &str| {
+            fs::File::options()
+                .write(true)
+                .open(dir.join(file))
+                .unwrap()
+                .set_modified(SystemTime::now() + Duration::from_secs(10))
+                .unwrap();
+        };
+        rotation.play(&dir.join("notes.txt"), 100);
+        touch("a.json");
+        assert!(!rotation.changed());
+        touch("notes.txt");
+        assert!(rotation.changed());
+        assert_eq!(Some(dir.join("notes.txt").as_path()), rotation.playing());
+
+        // matches count game time since their map was loaded
+        let ticks = (1. / constants::GAME_UPDATE_TICK_SECONDS) as u64;
+        assert!(!rotation.match_over(100 + ticks, Some(2.)));
+        assert!(rotation.match_over(100 + 2 * ticks, Some(2.)));
+        assert!(!rotation.match_over(100 + 2 * ticks, None));
+
+        rotation.resume(500);
+        touch("notes.txt");
+        assert!(!rotation.changed());
+        assert_eq!(None, rotation.playing());
+        assert_eq!(dir.join("b.json"), rotation.advance(600));
+        assert_eq!(Some(dir.join("b.json").as_path()), rotation.playing());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,22 @@
-mod object;
+pub mod body;
+pub mod checkpoint;
+pub mod control;
+pub mod deployable;
+pub mod env;
+pub mod field;
+pub mod fuel;
+pub mod generate;
+pub mod map;
+pub mod motion;
+pub mod object;
+pub mod orbit;
+pub mod pickup;
+pub mod replay;
 pub mod space;
 mod space_object;
+pub mod spawn;
+pub mod tether;
 pub mod update;
+pub mod validate;
+pub mod wall;
+pub mod zone;
@@ -0,0 +1,165 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Waypoint {
+    /// Seconds from the start
+    pub time: f64,
+    #[schemars(with = "[f64; 2]")]
+    pub location: DVec2,
+}
+
+/// How a planet moves. Planets on rails keep exerting their field but ignore all others.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Motion {
+    /// Pulled by the fields of the other planets
+    #[default]
+    Dynamic,
+    /// Stays at its location
+    Anchored,
+    /// Circles `center` at constant speed
+    Circle {
+        #[schemars(with = "[f64; 2]")]
+        center: DVec2,
+        radius: f64,
+        /// Seconds per round
+        period: f64,
+        /// Angle at the start in radians
+        #[serde(default)]
+        phase: f64,
+        #[serde(default)]
+        retrograde: bool,
+    },
+    /// Moves in straight lines between waypoints ordered by time
+    Waypoints {
+        waypoints: Vec<Waypoint>,
+        /// Start over after the last waypoint instead of stopping there
+        #[serde(default)]
+        repeat: bool,
+    },
+}
+
+impl Motion {
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, Motion::Dynamic)
+    }
+
+    /// Location and velocity at `time` seconds from the start for planets on a path.
+    pub fn state(&self, time: f64) -> Option<(DVec2, DVec2)> {
+        match self {
+            Motion::Dynamic | Motion::Anchored => None,
+            &Motion::Circle {
+                center,
+                radius,
+                period,
+                phase,
+                retrograde,
+            } => {
+                let turn = if retrograde { -TAU } else { TAU };
+                let angular_speed = turn / period;
+                let direction = DVec2::from_angle(phase + angular_speed * time);
+                Some((
+                    center + radius * direction,
+                    angular_speed * radius * direction.perp(),
+                ))
+            }
+            Motion::Waypoints { waypoints, repeat } => {
+                let first = waypoints.first()?;
+                let last = waypoints.last()?;
+                let time = match *repeat && last.time > 0. {
+                    true => time % last.time,
+                    false => time,
+                };
+                let next = waypoints.iter().position(|waypoint| waypoint.time > time);
+                match next {
+                    None => Some((last.location, DVec2::ZERO)),
+                    Some(0) => Some((first.location, DVec2::ZERO)),
+                    Some(index) => {
+                        let (from, to) = (&waypoints[index - 1], &waypoints[index]);
+                        let velocity = (to.location - from.location) / (to.time - from.time);
+                        Some((from.location + velocity * (time - from.time), velocity))
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::{ShipConfig, Space};
+
+    fn space() -> Space {
+        Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn circle_path() {
+        let motion = Motion::Circle {
+            center: DVec2::new(0.5, 0.5),
+            radius: 0.2,
+            period: 4.,
+            phase: 0.,
+            retrograde: false,
+        };
+        let (location, velocity) = motion.state(1.).unwrap();
+        assert!(location.distance(DVec2::new(0.5, 0.7)) < 1e-9);
+        assert!(velocity.distance(DVec2::new(-0.1 * std::f64::consts::PI, 0.)) < 1e-9);
+        assert!(motion.state(4.).unwrap().0.distance(DVec2::new(0.7, 0.5)) < 1e-9);
+    }
+
+    #[test]
+    fn waypoint_path() {
+        let waypoints = vec![
+            Waypoint {
+                time: 1.,
+                location: DVec2::new(0.2, 0.2),
+            },
+            Waypoint {
+                time: 3.,
+                location: DVec2::new(0.6, 0.2),
+            },
+        ];
+        let once = Motion::Waypoints {
+            waypoints: waypoints.clone(),
+            repeat: false,
+        };
+        assert_eq!(Some((DVec2::new(0.2, 0.2), DVec2::ZERO)), once.state(0.));
+        let (location, velocity) = once.state(2.).unwrap();
+        assert!(location.distance(DVec2::new(0.4, 0.2)) < 1e-9);
+        assert!(velocity.distance(DVec2::new(0.2, 0.)) < 1e-9);
+        assert_eq!(Some((DVec2::new(0.6, 0.2), DVec2::ZERO)), once.state(5.));
+        let repeated = Motion::Waypoints {
+            waypoints,
+            repeat: true,
+        };
+        assert_eq!(once.state(2.), repeated.state(5.));
+    }
+
+    #[test]
+    fn anchored_planet_attracts_without_moving() {
+        let mut space = space();
+        space.add_planet(DVec2::new(0.5, 0.5), 1., 1., 0.05, DVec2::ZERO);
+        space.add_planet(DVec2::new(0.2, 0.5), 1., 1., 0.05, DVec2::ZERO);
+        space.set_planet_motion(0, Motion::Anchored);
+        space.add_ship(1, DVec2::new(0.8, 0.5));
+        for _ in 0..100 {
+            space.update(0.001);
+        }
+        let planets: Vec<_> = space.planets().collect();
+        assert_eq!(DVec2::new(0.5, 0.5), planets[0].location);
+        assert!(planets[1].location.x > 0.2);
+        assert!(space.ship(1).unwrap().location.x < 0.8);
+    }
+}
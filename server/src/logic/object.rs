@@ -1,6 +1,8 @@
 use glam::f64::DVec2;
 use serde::{Deserialize, Serialize};
 
+use super::field::FieldLaw;
+
 pub trait Update {
     fn update(&mut self, time: f64, total_field: DVec2) {
         self.object_mut().update(time, total_field);
@@ -19,7 +21,7 @@ pub trait Update {
     fn object_mut(&mut self) -> &mut Object;
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Object {
     pub location: DVec2,
     pub radius: f64,
@@ -27,6 +29,8 @@ pub struct Object {
     pub field: f64,
     pub velocity: DVec2,
     pub acceleration: DVec2,
+    #[serde(default)]
+    pub law: FieldLaw,
 }
 
 impl Object {
@@ -38,6 +42,7 @@ impl Object {
             field,
             velocity,
             acceleration: DVec2::ZERO,
+            law: FieldLaw::InverseSquare,
         }
     }
 
@@ -50,18 +55,16 @@ impl Object {
         self.bounce();
     }
 
-    fn collision(&self, other: &Self) -> bool {
+    pub fn collision(&self, other: &Self) -> bool {
         self.location.distance(other.location) <= self.radius + other.radius
     }
 
-    fn get_field(&self, other: &Self) -> DVec2 {
-        if self.location.distance(other.location) < 1e-3 {
+    pub fn get_field(&self, other: &Self) -> DVec2 {
+        let distance = self.location.distance(other.location);
+        if distance < 1e-3 {
             return DVec2::ZERO;
         }
-        let effective_field_ratio =
-            self.location.distance(other.location).min(other.radius) / other.radius;
-        let value = (other.field * effective_field_ratio)
-            / (self.location.distance(other.location).powf(2.));
+        let value = other.law.strength(other.field, other.radius, distance);
         (other.location - self.location).normalize() * value
     }
 
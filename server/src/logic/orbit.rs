@@ -0,0 +1,104 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::object::Object;
+
+/// Keplerian orbit of a planet around a parent planet.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Orbit {
+    /// Name of the orbited planet, which has to come earlier in the map
+    pub parent: String,
+    pub semi_major_axis: f64,
+    /// 0 for a circle, up to but excluding 1
+    #[serde(default)]
+    pub eccentricity: f64,
+    /// Angle of the closest approach to the parent in radians
+    #[serde(default)]
+    pub periapsis: f64,
+    /// Angle from the periapsis at the start in radians
+    #[serde(default)]
+    pub phase: f64,
+    /// Orbit in the opposite direction
+    #[serde(default)]
+    pub retrograde: bool,
+}
+
+fn acceleration(object: &Object, other: &Object) -> DVec2 {
+    if object.mass == 0. {
+        return DVec2::ZERO;
+    }
+    object.get_field(other) / object.mass
+}
+
+impl Orbit {
+    pub fn periapsis_distance(&self) -> f64 {
+        self.semi_major_axis * (1. - self.eccentricity)
+    }
+
+    /// Location and velocity of `body` relative to `parent`. The attraction is taken
+    /// from `Object::get_field`, so orbits are exact as long as it falls off with the
+    /// square of the distance and circular orbits stay circular for any field law.
+    pub fn state(&self, parent: &Object, body: &Object) -> (DVec2, DVec2) {
+        let eccentricity = self.eccentricity;
+        let semi_latus_rectum = self.semi_major_axis * (1. - eccentricity.powi(2));
+        let distance = semi_latus_rectum / (1. + eccentricity * self.phase.cos());
+        let offset = distance * DVec2::from_angle(self.periapsis + self.phase);
+
+        // gravitational parameter of the pair from the fields at the start
+        let body = Object {
+            location: parent.location + offset,
+            ..body.clone()
+        };
+        let relative = acceleration(&body, parent) - acceleration(parent, &body);
+        let parameter = relative.length() * distance.powi(2);
+
+        let speed = (parameter / semi_latus_rectum).sqrt();
+        let perifocal = DVec2::new(-self.phase.sin(), eccentricity + self.phase.cos());
+        let velocity = DVec2::from_angle(self.periapsis).rotate(speed * perifocal);
+        match self.retrograde {
+            true => (offset, -velocity),
+            false => (offset, velocity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbit(eccentricity: f64, phase: f64) -> Orbit {
+        Orbit {
+            parent: String::from("sun"),
+            semi_major_axis: 0.2,
+            eccentricity,
+            periapsis: 1.,
+            phase,
+            retrograde: false,
+        }
+    }
+
+    #[test]
+    fn circular_orbit_state() {
+        let parent = Object::new(DVec2::new(0.5, 0.5), 0.05, 1000., 0.1, DVec2::ZERO);
+        let body = Object::new(DVec2::ZERO, 0.01, 1., 0., DVec2::ZERO);
+        let (offset, velocity) = orbit(0., 0.).state(&parent, &body);
+        assert!((offset.length() - 0.2).abs() < 1e-9);
+        assert!(offset.dot(velocity).abs() < 1e-9);
+        // v² / r equals the field strength per mass
+        assert!((velocity.length_squared() / 0.2 - 0.1 / 0.04).abs() < 1e-9);
+    }
+
+    #[test]
+    fn eccentric_orbit_is_fastest_at_periapsis() {
+        let parent = Object::new(DVec2::new(0.5, 0.5), 0.05, 1000., 0.1, DVec2::ZERO);
+        let body = Object::new(DVec2::ZERO, 0.01, 1., 0., DVec2::ZERO);
+        let (near, fast) = orbit(0.5, 0.).state(&parent, &body);
+        let (far, slow) = orbit(0.5, std::f64::consts::PI).state(&parent, &body);
+        assert!((near.length() - 0.1).abs() < 1e-9);
+        assert!((far.length() - 0.3).abs() < 1e-9);
+        // angular momentum is conserved
+        assert!((near.perp_dot(fast) - far.perp_dot(slow)).abs() < 1e-9);
+    }
+}
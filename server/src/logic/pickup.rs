@@ -0,0 +1,288 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ui::display::{DisplayInfo, DisplayType};
+
+use super::object::{Object, Update};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum PickupKind {
+    /// Multiplies the thrust
+    SpeedBoost { factor: f64, seconds: f64 },
+    /// Bounces off planets and walls instead of crashing
+    Shield { seconds: f64 },
+    /// Refills the energy pool of ships with limited fuel, which also powers shields,
+    /// mines and anchors, so it takes the place of extra ammo
+    Energy { amount: f64 },
+    /// Ignores the fields of planets
+    GravityImmunity { seconds: f64 },
+    /// Multiplies the mass, making the ship harder to push around
+    Mass { factor: f64, seconds: f64 },
+}
+
+/// Places pickups of one kind on the map on a timer.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Spawner {
+    pub kind: PickupKind,
+    /// Seconds between spawns
+    pub interval: f64,
+    /// Spawn location, a random free location if unset
+    #[serde(default)]
+    #[schemars(with = "Option<[f64; 2]>")]
+    pub location: Option<DVec2>,
+    /// Pickups of this spawner on the map at once
+    #[serde(default = "default_max")]
+    pub max: usize,
+    /// Whether planets pull the pickups
+    #[serde(default)]
+    pub gravity: bool,
+    #[serde(default = "default_radius")]
+    pub radius: f64,
+}
+
+fn default_max() -> usize {
+    1
+}
+
+fn default_radius() -> f64 {
+    0.008
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    object: Object,
+    gravity: bool,
+    /// Index of the spawner that placed it
+    pub spawner: usize,
+}
+
+impl Update for Pickup {
+    fn update(&mut self, time: f64, total_field: DVec2) {
+        if self.gravity {
+            self.object.update(time, total_field);
+        }
+    }
+
+    fn object(&self) -> &Object {
+        &self.object
+    }
+
+    fn object_mut(&mut self) -> &mut Object {
+        &mut self.object
+    }
+}
+
+impl Pickup {
+    pub fn new(spawner_index: usize, spawner: &Spawner, location: DVec2) -> Pickup {
+        Pickup {
+            kind: spawner.kind,
+            object: Object::new(location, spawner.radius, 1., 0., DVec2::ZERO),
+            gravity: spawner.gravity,
+            spawner: spawner_index,
+        }
+    }
+
+    pub fn get_display_info(&self) -> DisplayInfo {
+        DisplayInfo {
+            display_type: DisplayType::Pickup,
+            id: None,
+            x: self.object.location.x,
+            y: self.object.location.y,
+            radius: self.object.radius,
+            heading: None,
+        }
+    }
+}
+
+/// Timed effect of a collected pickup.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Buff {
+    pub kind: PickupKind,
+    pub remaining: f64,
+}
+
+/// Active effects of a ship.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Buffs(Vec<Buff>);
+
+impl Buffs {
+    /// Starts the effect of a timed pickup, returns false for instant ones.
+    pub fn add(&mut self, kind: PickupKind) -> bool {
+        let seconds = match kind {
+            PickupKind::SpeedBoost { seconds, .. }
+            | PickupKind::Shield { seconds }
+            | PickupKind::GravityImmunity { seconds }
+            | PickupKind::Mass { seconds, .. } => seconds,
+            PickupKind::Energy { .. } => return false,
+        };
+        self.0.push(Buff {
+            kind,
+            remaining: seconds,
+        });
+        true
+    }
+
+    pub fn tick(&mut self, time: f64) {
+        for buff in self.0.iter_mut() {
+            buff.remaining -= time;
+        }
+        self.0.retain(|buff| buff.remaining > 0.);
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn thrust_factor(&self) -> f64 {
+        self.0
+            .iter()
+            .map(|buff| match buff.kind {
+                PickupKind::SpeedBoost { factor, .. } => factor,
+                _ => 1.,
+            })
+            .product()
+    }
+
+    pub fn mass_factor(&self) -> f64 {
+        self.0
+            .iter()
+            .map(|buff| match buff.kind {
+                PickupKind::Mass { factor, .. } => factor,
+                _ => 1.,
+            })
+            .product()
+    }
+
+    pub fn shielded(&self) -> bool {
+        self.0
+            .iter()
+            .any(|buff| matches!(buff.kind, PickupKind::Shield { .. }))
+    }
+
+    pub fn gravity_immune(&self) -> bool {
+        self.0
+            .iter()
+            .any(|buff| matches!(buff.kind, PickupKind::GravityImmunity { .. }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::{ShipConfig, Space};
+
+    fn space() -> Space {
+        Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn buffs_stack_and_expire() {
+        let mut buffs = Buffs::default();
+        assert!(buffs.add(PickupKind::SpeedBoost {
+            factor: 2.,
+            seconds: 1.,
+        }));
+        assert!(buffs.add(PickupKind::SpeedBoost {
+            factor: 3.,
+            seconds: 3.,
+        }));
+        assert!(buffs.add(PickupKind::Shield { seconds: 2. }));
+        assert!(!buffs.add(PickupKind::Energy { amount: 1. }));
+        assert_eq!(6., buffs.thrust_factor());
+        assert!(buffs.shielded());
+        buffs.tick(1.5);
+        assert_eq!(3., buffs.thrust_factor());
+        assert!(buffs.shielded());
+        buffs.tick(1.);
+        assert!(!buffs.shielded());
+        assert!(!buffs.gravity_immune());
+        assert_eq!(1., buffs.mass_factor());
+    }
+
+    #[test]
+    fn pickups_spawn_on_timer_and_are_collected() {
+        let mut space = space();
+        space.set_spawners(vec![
+            Spawner {
+                kind: PickupKind::Shield { seconds: 1. },
+                interval: 0.5,
+                location: Some(DVec2::new(0.5, 0.5)),
+                max: 1,
+                gravity: false,
+                radius: 0.01,
+            },
+            Spawner {
+                kind: PickupKind::SpeedBoost {
+                    factor: 2.,
+                    seconds: 1.,
+                },
+                interval: 0.5,
+                location: None,
+                max: 2,
+                gravity: true,
+                radius: 0.01,
+            },
+        ]);
+        space.update(0.3);
+        assert_eq!(0, space.pickups().count());
+        space.update(0.3);
+        space.update(0.5);
+        space.update(0.5);
+        // the fixed spawner stays at its maximum
+        assert_eq!(3, space.pickups().count());
+
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.update(0.);
+        assert_eq!(2, space.pickups().count());
+        assert!(space.ship_mut(1).unwrap().buffs().shielded());
+        // shields bounce ships off planets until they run out
+        space.add_planet(DVec2::new(0.5, 0.5), 0., 0., 0.05, DVec2::ZERO);
+        space.update(0.5);
+        assert_eq!(Some(0), space.deaths(1));
+        let location = space.ship(1).unwrap().location;
+        assert!(location.distance(DVec2::new(0.5, 0.5)) > 0.05 + 0.1);
+        space.ship_mut(1).unwrap().object_mut().location = DVec2::new(0.5, 0.5);
+        space.update(0.6);
+        assert_eq!(Some(1), space.deaths(1));
+    }
+
+    #[test]
+    fn buffs_change_ship_motion() {
+        let mut space = space();
+        space.add_planet(DVec2::new(0.2, 0.5), 1., 1., 0.05, DVec2::ZERO);
+        space.add_ship(1, DVec2::new(0.8, 0.5));
+        space.add_ship(2, DVec2::new(0.8, 0.5));
+        space
+            .ship_mut(1)
+            .unwrap()
+            .collect(PickupKind::GravityImmunity { seconds: 1. });
+        space.update(0.1);
+        assert_eq!(DVec2::ZERO, space.ship(1).unwrap().velocity);
+        assert!(space.ship(2).unwrap().velocity.x < 0.);
+
+        let ship = space.ship_mut(1).unwrap();
+        ship.collect(PickupKind::Mass {
+            factor: 2.,
+            seconds: 1.,
+        });
+        ship.collect(PickupKind::SpeedBoost {
+            factor: 4.,
+            seconds: 1.,
+        });
+        space.move_ship(1, Some(0.));
+        space.update(0.1);
+        // twice the thrust of 0.1 on a mass of 2
+        let acceleration = space.ship(1).unwrap().acceleration;
+        assert!((acceleration - DVec2::new(0.1, 0.)).length() < 1e-9);
+    }
+}
@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::constants;
+use crate::ui::command;
+
+use super::space::{FullState, Space};
+
+pub const REPLAY_FORMAT: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub format: u32,
+    pub version: String,
+    pub map: String,
+    pub seed: u64,
+    pub tick_seconds: f64,
+}
+
+impl ReplayHeader {
+    pub fn new(map: &str, seed: u64) -> ReplayHeader {
+        ReplayHeader {
+            format: REPLAY_FORMAT,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            map: map.to_string(),
+            seed,
+            tick_seconds: constants::GAME_UPDATE_TICK_SECONDS,
+        }
+    }
+}
+
+/// Replay file content following the header and the initial `Space` keyframe.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ReplayRecord {
+    /// Commands applied before updating the space at `tick`
+    Commands { tick: u64, commands: Vec<Vec<u8>> },
+    /// Space at its tick, after the commands recorded before it at the same tick,
+    /// which can be several while the game is paused
+    Keyframe(Box<FullState>),
+    /// Tick the recording stopped at, the last record of a complete replay
+    End { tick: u64 },
+}
+
+/// Writes replay records on a separate thread so the game loop only pays for sending them.
+pub struct ReplayRecorder {
+    sender: Sender<ReplayRecord>,
+    handle: Option<JoinHandle<()>>,
+    /// Latest tick of a recorded space
+    tick: Cell<u64>,
+}
+
+impl ReplayRecorder {
+    /// Starts a new replay file in `dir`, returns the recorder and the file path.
+    pub fn create(
+        dir: &str,
+        header: &ReplayHeader,
+        space: &Space,
+    ) -> Result<(ReplayRecorder, PathBuf), Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let path = Path::new(dir).join(format!("{timestamp}-{}.replay", header.seed));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        bincode::serialize_into(&mut writer, header)?;
+        bincode::serialize_into(&mut writer, &FullState(space.clone()))?;
+        writer.flush()?;
+
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            for record in receiver {
+                let keyframe = matches!(record, ReplayRecord::Keyframe(_));
+                let mut result =
+                    bincode::serialize_into(&mut writer, &record).map_err(|e| e.to_string());
+                if keyframe && result.is_ok() {
+                    result = writer.flush().map_err(|e| e.to_string());
+                }
+                if let Err(e) = result {
+                    eprintln!("Replay error: {e}");
+                    return;
+                }
+            }
+            writer.flush().ok();
+        });
+        let recorder = ReplayRecorder {
+            sender,
+            handle: Some(handle),
+            tick: Cell::new(space.get_tick()),
+        };
+        Ok((recorder, path))
+    }
+
+    pub fn record_commands(&self, tick: u64, commands: Vec<Vec<u8>>) {
+        if !commands.is_empty() {
+            self.sender
+                .send(ReplayRecord::Commands { tick, commands })
+                .ok();
+        }
+    }
+
+    /// Records a keyframe of a space changed outside of player commands.
+    pub fn record_snapshot(&self, space: &Space) {
+        self.tick.set(space.get_tick());
+        let keyframe = Box::new(FullState(space.clone()));
+        self.sender.send(ReplayRecord::Keyframe(keyframe)).ok();
+    }
+
+    /// Records a keyframe every `REPLAY_KEYFRAME_TICKS` ticks.
+    pub fn record_keyframe(&self, space: &Space) {
+        self.tick.set(space.get_tick());
+        if space
+            .get_tick()
+            .is_multiple_of(constants::REPLAY_KEYFRAME_TICKS)
+        {
+            self.record_snapshot(space);
+        }
+    }
+}
+
+impl Drop for ReplayRecorder {
+    /// Ends the replay at the last recorded tick and waits until all records are written.
+    fn drop(&mut self) {
+        let tick = self.tick.get();
+        self.sender.send(ReplayRecord::End { tick }).ok();
+        let (closed_sender, _) = mpsc::channel();
+        drop(std::mem::replace(&mut self.sender, closed_sender));
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+pub struct Replay {
+    pub header: ReplayHeader,
+    keyframes: Vec<Space>,
+    /// Index of the last keyframe recorded at a tick
+    keyframe_ticks: HashMap<u64, usize>,
+    commands: HashMap<u64, Vec<Vec<u8>>>,
+    last_tick: u64,
+}
+
+impl Replay {
+    pub fn read(path: &str) -> Result<Replay, Box<dyn std::error::Error>> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let header: ReplayHeader = bincode::deserialize_from(&mut reader)?;
+        if header.format != REPLAY_FORMAT {
+            return Err(format!("unsupported replay format {}", header.format).into());
+        }
+        let first: FullState = bincode::deserialize_from(&mut reader)?;
+        let mut keyframes = vec![first.0];
+        let mut commands = HashMap::new();
+        let mut last_tick = keyframes[0].get_tick();
+        loop {
+            match bincode::deserialize_from(&mut reader) {
+                Ok(ReplayRecord::Commands {
+                    tick,
+                    commands: tick_commands,
+                }) => {
+                    last_tick = last_tick.max(tick + 1);
+                    commands
+                        .entry(tick)
+                        .or_insert_with(Vec::new)
+                        .extend(tick_commands);
+                }
+                Ok(ReplayRecord::Keyframe(state)) => {
+                    last_tick = last_tick.max(state.0.get_tick());
+                    // the keyframe already contains them
+                    commands.remove(&state.0.get_tick());
+                    keyframes.push(state.0);
+                }
+                Ok(ReplayRecord::End { tick }) => last_tick = last_tick.max(tick),
+                // a partially written last record ends the replay
+                Err(e) => match *e {
+                    bincode::ErrorKind::Io(ref e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                    _ => return Err(e),
+                },
+            }
+        }
+        let keyframe_ticks = keyframes
+            .iter()
+            .enumerate()
+            .map(|(index, keyframe)| (keyframe.get_tick(), index))
+            .collect();
+        Ok(Replay {
+            header,
+            keyframes,
+            keyframe_ticks,
+            commands,
+            last_tick,
+        })
+    }
+
+    pub fn first_tick(&self) -> u64 {
+        self.keyframes[0].get_tick()
+    }
+
+    pub fn last_tick(&self) -> u64 {
+        self.last_tick
+    }
+
+    /// Rebuilds the space at `tick` from the closest preceding keyframe.
+    pub fn state_at(&self, tick: u64) -> Space {
+        let mut space = self.keyframes[0].clone();
+        self.seek(&mut space, tick);
+        space
+    }
+
+    /// Moves `space` to `tick`, simulating forward from it or from the closest
+    /// preceding keyframe, whichever is closer.
+    pub fn seek(&self, space: &mut Space, tick: u64) {
+        let tick = tick.clamp(self.first_tick(), self.last_tick);
+        let keyframe = self
+            .keyframes
+            .iter()
+            .rev()
+            .find(|keyframe| keyframe.get_tick() <= tick)
+            .unwrap_or(&self.keyframes[0]);
+        if space.get_tick() > tick || space.get_tick() <= keyframe.get_tick() {
+            *space = keyframe.clone();
+        }
+        while space.get_tick() < tick {
+            self.step(space);
+        }
+    }
+
+    /// Advances `space` by one recorded tick.
+    pub fn step(&self, space: &mut Space) {
+        // keyframes also carry changes made outside of player commands
+        if let Some(&index) = self.keyframe_ticks.get(&space.get_tick()) {
+            *space = self.keyframes[index].clone();
+        }
+        if let Some(commands) = self.commands.get(&space.get_tick()) {
+            for command in commands {
+                command::run_command(space, command);
+            }
+        }
+        space.update(self.header.tick_seconds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::ShipConfig;
+    use glam::DVec2;
+
+    fn basic_space() -> Space {
+        let mut space = Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.01,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        });
+        space.seed(3);
+        space.add_planet(DVec2::new(0.5, 0.5), 1., 0.1, 0.1, DVec2::ZERO);
+        space
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_game() {
+        let dir = std::env::temp_dir().join("space_game_replay_test");
+        let mut space = basic_space();
+        let (recorder, path) =
+            ReplayRecorder::create(dir.to_str().unwrap(), &ReplayHeader::new("test", 3), &space)
+                .unwrap();
+        let mut states = vec![];
+        for tick in 0..2500u64 {
+            let commands = match tick {
+                10 => vec![vec![0, 1], vec![0, 2]],
+                20 => vec![vec![2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]],
+                1500 => vec![vec![1, 2]],
+                _ => vec![],
+            };
+            for command in commands.iter() {
+                command::run_command(&mut space, command);
+            }
+            recorder.record_commands(space.get_tick(), commands);
+            states.push(space.get_state_binary());
+            space.update(constants::GAME_UPDATE_TICK_SECONDS);
+            recorder.record_keyframe(&space);
+        }
+        drop(recorder);
+
+        let replay = Replay::read(path.to_str().unwrap()).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(0, replay.first_tick());
+        assert_eq!(2500, replay.last_tick());
+        for tick in [0, 15, 999, 1000, 1700, 2000, 2499] {
+            let mut space = replay.state_at(tick);
+            for command in replay.commands.get(&tick).into_iter().flatten() {
+                command::run_command(&mut space, command);
+            }
+            assert_eq!(states[tick as usize], space.get_state_binary());
+        }
+    }
+    #[test]
+    fn snapshots_while_paused_do_not_repeat_commands() {
+        let dir = std::env::temp_dir().join("space_game_replay_pause_test");
+        let mut space = basic_space();
+        let (recorder, path) =
+            ReplayRecorder::create(dir.to_str().unwrap(), &ReplayHeader::new("test", 3), &space)
+                .unwrap();
+        // paused game loop iterations at tick 0, each ending with an admin snapshot
+        for id in 1..=3 {
+            let command = vec![0, id];
+            command::run_command(&mut space, &command);
+            recorder.record_commands(space.get_tick(), vec![command]);
+            recorder.record_snapshot(&space);
+        }
+        let command = vec![0, 4];
+        command::run_command(&mut space, &command);
+        recorder.record_commands(space.get_tick(), vec![command]);
+        space.update(constants::GAME_UPDATE_TICK_SECONDS);
+        drop(recorder);
+
+        let replay = Replay::read(path.to_str().unwrap()).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(
+            space.get_state_binary(),
+            replay.state_at(1).get_state_binary()
+        );
+    }
+}
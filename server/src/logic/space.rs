@@ -1,60 +1,390 @@
 use glam::DVec2;
-use rand::Rng;
-use serde::{Deserialize, Serialize};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::{
-    object::Update,
+    body::{self, BodyKind},
+    control::{ControlModel, Steering},
+    deployable::{AnchorConfig, DeployKind, Deployable, MineConfig},
+    field::FieldLaw,
+    fuel::FuelConfig,
+    motion::Motion,
+    object::{Object, Update},
+    pickup::{Pickup, Spawner},
     space_object::{Planet, Ship},
+    spawn::{SpawnConfig, SpawnPoint},
+    tether::{Target, Tether, TetherConfig},
+    wall::{Hit, Wall},
+    zone::{self, Zone},
 };
-use crate::ui::display::Drawer;
+use crate::ui::display::{DisplayType, Drawer, LineInfo};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Space {
     ship_config: ShipConfig,
     planets: Vec<Planet>,
     ships: Vec<Ship>,
+    #[serde(default)]
+    spawn: SpawnConfig,
+    /// Named locations among the spawn areas
+    #[serde(default)]
+    spawn_points: Vec<SpawnPoint>,
+    #[serde(default)]
+    tick: u64,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    zones: Vec<Zone>,
+    #[serde(default)]
+    walls: Vec<Wall>,
+    #[serde(default)]
+    pickups: Vec<Pickup>,
+    #[serde(default)]
+    deployables: Vec<Deployable>,
+    #[serde(default)]
+    tethers: Vec<Tether>,
+    /// Only saved with `FullState`, never sent to clients
+    #[serde(skip)]
+    server: ServerState,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+/// State that would let clients predict spawns, kept out of the states they receive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerState {
+    seed: Option<u64>,
+    rng: ChaCha8Rng,
+    spawners: Vec<Spawner>,
+    /// Seconds since the last spawn of every spawner
+    spawn_timers: Vec<f64>,
+}
+
+impl Default for ServerState {
+    fn default() -> Self {
+        ServerState {
+            seed: None,
+            rng: ChaCha8Rng::seed_from_u64(0),
+            spawners: vec![],
+            spawn_timers: vec![],
+        }
+    }
+}
+
+/// Space together with its `ServerState`, for checkpoints and replays.
+#[derive(Debug, Clone)]
+pub struct FullState(pub Space);
+
+impl Serialize for FullState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.0, &self.0.server).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FullState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (mut space, server): (Space, ServerState) = Deserialize::deserialize(deserializer)?;
+        space.server = server;
+        Ok(FullState(space))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
 pub struct ShipConfig {
     pub force: f64,
     pub radius: f64,
     pub mass: f64,
     pub field: f64,
+    #[serde(default)]
+    pub controls: ControlModel,
+    /// Energy for thrust, unlimited if unset
+    #[serde(default)]
+    pub fuel: Option<FuelConfig>,
+    /// Mines ships can drop, none if unset
+    #[serde(default)]
+    pub mines: Option<MineConfig>,
+    /// Gravity anchors ships can drop, none if unset
+    #[serde(default)]
+    pub anchors: Option<AnchorConfig>,
+    /// Tethers ships can latch on with, none if unset
+    #[serde(default)]
+    pub tether: Option<TetherConfig>,
 }
 
 impl Space {
-    #[cfg(test)]
     pub fn new(ship_config: ShipConfig) -> Space {
         Space {
             ship_config,
             planets: vec![],
             ships: vec![],
+            spawn: SpawnConfig::default(),
+            spawn_points: vec![],
+            tick: 0,
+            message: None,
+            zones: vec![],
+            walls: vec![],
+            pickups: vec![],
+            deployables: vec![],
+            tethers: vec![],
+            server: ServerState::default(),
         }
     }
 
+    /// Restarts all randomness of the simulation from `seed`.
+    pub fn seed(&mut self, seed: u64) {
+        self.server.seed = Some(seed);
+        self.server.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    pub fn get_seed(&self) -> Option<u64> {
+        self.server.seed
+    }
+
+    pub fn set_spawn_config(&mut self, spawn: SpawnConfig) {
+        self.spawn = spawn;
+    }
+
+    pub fn set_spawn_points(&mut self, spawn_points: Vec<SpawnPoint>) {
+        self.spawn_points = spawn_points;
+    }
+
+    pub fn spawn_points(&self) -> &[SpawnPoint] {
+        &self.spawn_points
+    }
+
+    pub fn set_zones(&mut self, zones: Vec<Zone>) {
+        self.zones = zones;
+    }
+
+    pub fn zones(&self) -> &[Zone] {
+        &self.zones
+    }
+
+    pub fn set_walls(&mut self, walls: Vec<Wall>) {
+        self.walls = walls;
+    }
+
+    pub fn set_spawners(&mut self, spawners: Vec<Spawner>) {
+        self.server.spawn_timers = vec![0.; spawners.len()];
+        self.server.spawners = spawners;
+        self.pickups.clear();
+    }
+
+    pub fn pickups(&self) -> impl Iterator<Item = &Pickup> {
+        self.pickups.iter()
+    }
+
+    pub fn gen_new_location(&mut self) -> DVec2 {
+        self.gen_spawn_location(None)
+    }
+
+    /// State as sent to clients, without the `ServerState`.
     pub fn get_state_binary(&self) -> Vec<u8> {
         bincode::serialize(&self).unwrap()
     }
 
+    /// State as seen by the player of ship `viewer`, without the ships of others
+    /// hidden in blackout zones, their deployables and the tethers they are on.
+    pub fn get_state_binary_for(&self, viewer: Option<u8>) -> Vec<u8> {
+        let hidden: Vec<u8> = self
+            .ships
+            .iter()
+            .filter(|ship| {
+                Some(ship.get_id()) != viewer
+                    && zone::is_hidden(&self.zones, ship.object().location)
+            })
+            .map(|ship| ship.get_id())
+            .collect();
+        if hidden.is_empty() {
+            return self.get_state_binary();
+        }
+        let mut view = self.clone();
+        view.ships.retain(|ship| !hidden.contains(&ship.get_id()));
+        view.deployables
+            .retain(|deployable| !hidden.contains(&deployable.owner));
+        view.tethers.retain(|tether| {
+            !hidden.contains(&tether.owner)
+                && !matches!(tether.target, Target::Ship(id) if hidden.contains(&id))
+        });
+        view.get_state_binary()
+    }
+
     pub fn draw_all(
         &self,
         drawer: &mut impl Drawer,
         width: u32,
         height: u32,
     ) -> Result<(), String> {
+        for wall in self.walls.iter() {
+            let display_type = match wall.hit {
+                Hit::Bounce => DisplayType::Wall,
+                Hit::Destroy => DisplayType::DeadlyWall,
+            };
+            for (from, to) in wall.edges() {
+                let line_info = LineInfo {
+                    display_type,
+                    from: (from.x, from.y),
+                    to: (to.x, to.y),
+                };
+                drawer.draw_line(line_info, width, height)?;
+            }
+        }
         for planet in self.planets.iter() {
             drawer.draw(planet.get_display_info(), width, height)?;
         }
+        for pickup in self.pickups.iter() {
+            drawer.draw(pickup.get_display_info(), width, height)?;
+        }
+        for deployable in self.deployables.iter() {
+            drawer.draw(deployable.get_display_info(), width, height)?;
+        }
+        for tether in self.tethers.iter() {
+            let (Some(from), Some(to)) = (self.ship(tether.owner), self.target(tether.target))
+            else {
+                continue;
+            };
+            let line_info = LineInfo {
+                display_type: DisplayType::Tether,
+                from: (from.location.x, from.location.y),
+                to: (to.location.x, to.location.y),
+            };
+            drawer.draw_line(line_info, width, height)?;
+        }
         for ship in self.ships.iter() {
             drawer.draw(ship.get_display_info(), width, height)?;
         }
         Ok(())
     }
 
+    pub fn planets(&self) -> impl Iterator<Item = &Object> {
+        self.planets.iter().map(|planet| planet.object())
+    }
+
+    pub fn ships(&self) -> impl Iterator<Item = (u8, &Object)> {
+        self.ships.iter().map(|ship| (ship.get_id(), ship.object()))
+    }
+
+    pub fn ship(&self, id: u8) -> Option<&Object> {
+        self.get_ship_index(id)
+            .map(|index| self.ships[index].object())
+    }
+
+    #[cfg(test)]
+    pub fn ship_mut(&mut self, id: u8) -> Option<&mut Ship> {
+        self.get_ship_index(id).map(|index| &mut self.ships[index])
+    }
+
+    pub fn field(&self, id: u8) -> Option<DVec2> {
+        self.get_ship_index(id).map(|index| {
+            let ship = &self.ships[index];
+            self.planets.iter().map(|other| ship.get_field(other)).sum()
+        })
+    }
+
+    pub fn deaths(&self, id: u8) -> Option<u32> {
+        self.get_ship_index(id)
+            .map(|index| self.ships[index].get_deaths())
+    }
+
+    pub fn get_message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn set_message(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+
+    /// Switches to the bodies and configuration of `map`, keeping the ships of
+    /// connected players with their ids and scores.
+    pub fn replace_map(&mut self, map: Space) {
+        let ids: Vec<u8> = self.ships.iter().map(|ship| ship.get_id()).collect();
+        self.ship_config = map.ship_config;
+        self.planets = map.planets;
+        self.spawn = map.spawn;
+        self.spawn_points = map.spawn_points;
+        self.zones = map.zones;
+        self.walls = map.walls;
+        self.set_spawners(map.server.spawners);
+        self.deployables.clear();
+        self.tethers.clear();
+        for id in ids {
+            let location = self.gen_spawn_location(Some(id));
+            let index = self.get_ship_index(id).unwrap();
+            self.ships[index].reconfigure(location, &self.ship_config);
+        }
+    }
+
+    pub fn add_planet(
+        &mut self,
+        location: DVec2,
+        mass: f64,
+        field: f64,
+        radius: f64,
+        velocity: DVec2,
+    ) {
+        self.planets
+            .push(Planet::new(location, mass, field, radius, velocity));
+    }
+
+    pub fn set_planet_motion(&mut self, index: usize, motion: Motion) {
+        if let Some(planet) = self.planets.get_mut(index) {
+            planet.set_motion(motion);
+        }
+    }
+
+    pub fn set_planet_field_law(&mut self, index: usize, law: FieldLaw) {
+        if let Some(planet) = self.planets.get_mut(index) {
+            planet.object_mut().law = law;
+        }
+    }
+
+    pub fn set_planet_kind(&mut self, index: usize, kind: BodyKind) {
+        if let Some(planet) = self.planets.get_mut(index) {
+            planet.set_kind(kind);
+        }
+    }
+
+    pub fn remove_planet(&mut self, index: usize) -> bool {
+        if index >= self.planets.len() {
+            return false;
+        }
+        self.planets.remove(index);
+        self.tethers
+            .retain(|tether| tether.target != Target::Planet(index));
+        for tether in self.tethers.iter_mut() {
+            if let Target::Planet(target) = &mut tether.target {
+                if *target > index {
+                    *target -= 1;
+                }
+            }
+        }
+        // keep wormholes pointing at the same exits
+        for planet in self.planets.iter_mut() {
+            if let &BodyKind::Wormhole { exit } = planet.kind() {
+                match exit.cmp(&index) {
+                    std::cmp::Ordering::Less => {}
+                    std::cmp::Ordering::Equal => planet.set_kind(BodyKind::Planet),
+                    std::cmp::Ordering::Greater => {
+                        planet.set_kind(BodyKind::Wormhole { exit: exit - 1 })
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
     pub fn update(&mut self, time: f64) {
+        self.update_tethers(time);
         self.update_planets(time);
         self.update_ships(time);
+        self.update_deployables(time);
+        self.update_pickups(time);
+        self.tick += 1;
     }
 
     pub fn add_ship(&mut self, id: u8, location: DVec2) {
@@ -68,6 +398,8 @@ impl Space {
         if let Some(index) = index {
             self.ships.remove(index);
         }
+        self.deployables.retain(|deployable| deployable.owner != id);
+        self.release_tethers(id);
     }
 
     pub fn move_ship(&mut self, id: u8, direction: Option<f64>) {
@@ -77,6 +409,122 @@ impl Space {
         }
     }
 
+    /// Stops the thrust and steering of all ships, whose players are gone after resuming.
+    pub fn clear_input(&mut self) {
+        for ship in self.ships.iter_mut() {
+            ship.change_direction(None);
+            ship.steer(Steering::default());
+        }
+    }
+
+    pub fn steer_ship(&mut self, id: u8, steering: Steering) {
+        if let Some(index) = self.get_ship_index(id) {
+            self.ships[index].steer(steering);
+        }
+    }
+
+    /// Drops a mine or anchor where the ship is, unless it has all of them out already.
+    pub fn deploy(&mut self, id: u8, kind: DeployKind) {
+        let Some(index) = self.get_ship_index(id) else {
+            return;
+        };
+        let location = self.ships[index].object().location;
+        let count = self
+            .deployables
+            .iter()
+            .filter(|deployable| deployable.owner == id && deployable.kind == kind)
+            .count();
+        let deployable = match (kind, self.ship_config.mines, self.ship_config.anchors) {
+            (DeployKind::Mine, Some(config), _) if count < config.max => {
+                Deployable::mine(id, location, &config)
+            }
+            (DeployKind::Anchor, _, Some(config)) if count < config.max => {
+                Deployable::anchor(id, location, &config)
+            }
+            _ => return,
+        };
+        if self.ships[index].spend(|fuel| fuel.deploy_cost) {
+            self.deployables.push(deployable);
+        }
+    }
+
+    pub fn deployables(&self) -> impl Iterator<Item = &Deployable> {
+        self.deployables.iter()
+    }
+
+    /// Latches onto the closest planet or ship in range, unless the ship is tethered already.
+    pub fn latch_tether(&mut self, id: u8) {
+        if self.tethers.iter().any(|tether| tether.owner == id) {
+            return;
+        }
+        let (Some(config), Some(ship)) = (self.ship_config.tether, self.ship(id)) else {
+            return;
+        };
+        let planets = (0..self.planets.len()).map(Target::Planet);
+        let ships = self
+            .ships
+            .iter()
+            .map(|other| Target::Ship(other.get_id()))
+            .filter(|&target| target != Target::Ship(id));
+        let closest = planets
+            .chain(ships)
+            .filter_map(|target| {
+                let other = self.target(target)?;
+                let length = ship.location.distance(other.location);
+                Some((target, length - ship.radius - other.radius, length))
+            })
+            .filter(|&(_, gap, _)| gap <= config.range)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((target, _, length)) = closest {
+            self.tethers.push(Tether {
+                owner: id,
+                target,
+                length,
+            });
+        }
+    }
+
+    pub fn release_tether(&mut self, id: u8) {
+        self.tethers.retain(|tether| tether.owner != id);
+    }
+
+    pub fn tethers(&self) -> impl Iterator<Item = &Tether> {
+        self.tethers.iter()
+    }
+
+    fn target(&self, target: Target) -> Option<&Object> {
+        match target {
+            Target::Planet(index) => self.planets.get(index).map(|planet| planet.object()),
+            Target::Ship(id) => self.ship(id),
+        }
+    }
+
+    fn release_tethers(&mut self, id: u8) {
+        self.tethers
+            .retain(|tether| tether.owner != id && tether.target != Target::Ship(id));
+    }
+
+    pub fn ship_config(&self) -> &ShipConfig {
+        &self.ship_config
+    }
+
+    /// Energy left and capacity of the ship, `None` if it has unlimited fuel.
+    pub fn fuel(&self, id: u8) -> Option<(f64, f64)> {
+        self.get_ship_index(id)
+            .and_then(|index| self.ships[index].fuel_gauge())
+    }
+
+    pub fn heading(&self, id: u8) -> Option<f64> {
+        self.get_ship_index(id)
+            .map(|index| self.ships[index].heading())
+    }
+
+    /// Angular speed of the ship in radians per second.
+    pub fn spin(&self, id: u8) -> Option<f64> {
+        self.get_ship_index(id)
+            .map(|index| self.ships[index].spin())
+    }
+
     fn update_planets(&mut self, time: f64) {
         let total_planet_fields: Vec<DVec2> = self
             .planets
@@ -85,43 +533,301 @@ impl Space {
                 self.planets
                     .iter()
                     .map(|other| planet.get_field(other))
-                    .sum()
+                    .sum::<DVec2>()
+                    + zone::force(&self.zones, planet.object())
             })
             .collect();
         for (i, planet) in self.planets.iter_mut().enumerate() {
             planet.update(time, total_planet_fields[i]);
         }
+        let wormholes = self.wormholes();
+        for (i, planet) in self.planets.iter_mut().enumerate() {
+            let passes = matches!(planet.kind(), BodyKind::Planet) && planet.is_dynamic();
+            if let Some((_, entry, exit)) = wormholes
+                .iter()
+                .find(|(index, entry, _)| passes && *index != i && planet.object().collision(entry))
+            {
+                body::teleport(planet.object_mut(), entry, exit);
+            }
+        }
+    }
+
+    /// Index, entry and exit of every wormhole with an existing exit.
+    fn wormholes(&self) -> Vec<(usize, Object, Object)> {
+        self.planets
+            .iter()
+            .enumerate()
+            .filter_map(|(index, planet)| match planet.kind() {
+                &BodyKind::Wormhole { exit } => Some((
+                    index,
+                    planet.object().clone(),
+                    self.planets.get(exit)?.object().clone(),
+                )),
+                _ => None,
+            })
+            .collect()
     }
 
     fn update_ships(&mut self, time: f64) {
-        for ship in self.ships.iter_mut() {
-            let total_field = self.planets.iter().map(|other| ship.get_field(other)).sum();
-            ship.update(time, total_field);
-            if self.planets.iter().any(|planet| ship.collision(planet)) {
-                ship.respawn(gen_new_location());
+        let wormholes = self.wormholes();
+        let mut crashed = vec![];
+        for (i, ship) in self.ships.iter_mut().enumerate() {
+            ship.rotate(time);
+            let object = ship.object();
+            let previous = object.location;
+            let fields: Vec<DVec2> = self
+                .planets
+                .iter()
+                .map(|other| ship.get_field(other))
+                .collect();
+            // anchors only bend the paths of others
+            let anchor_field: DVec2 = self
+                .deployables
+                .iter()
+                .filter(|anchor| anchor.kind == DeployKind::Anchor)
+                .filter(|anchor| anchor.owner != ship.get_id())
+                .map(|anchor| ship.get_field(anchor))
+                .sum();
+            let total_field: DVec2 = match ship.buffs().gravity_immune() {
+                true => DVec2::ZERO,
+                false => fields.iter().sum::<DVec2>() + anchor_field,
+            };
+            let thrust = ship.thrust()
+                * zone::thrust_factor(&self.zones, object.location)
+                * ship.buffs().thrust_factor();
+            let zone_force = zone::force(&self.zones, object);
+            let refuel = zone::refuel_rate(&self.zones, object.location);
+            let thrust = ship.burn(thrust, time);
+            let field_strength = fields.iter().map(|field| field.length()).sum();
+            ship.regenerate(field_strength, refuel, time);
+            let mass_factor = ship.buffs().mass_factor();
+            ship.update(time, (total_field + thrust + zone_force) / mass_factor);
+            ship.buffs_mut().tick(time);
+            // contact points and normals of the deadly walls and planets hit
+            let mut hits = vec![];
+            for wall in self.walls.iter() {
+                if let Some((contact, normal)) = wall.contact(previous, ship.object()) {
+                    match wall.hit {
+                        Hit::Bounce => Wall::bounce(ship.object_mut(), contact, normal),
+                        Hit::Destroy => hits.push((contact, normal)),
+                    }
+                }
+            }
+            if let Some((_, entry, exit)) = wormholes
+                .iter()
+                .find(|(_, entry, _)| ship.object().collision(entry))
+            {
+                body::teleport(ship.object_mut(), entry, exit);
+            }
+            for planet in self.planets.iter().filter(|planet| planet.destroys(ship)) {
+                let center = planet.object().location;
+                let normal = (ship.object().location - center)
+                    .try_normalize()
+                    .unwrap_or(DVec2::X);
+                hits.push((center + normal * planet.danger_radius(), normal));
+            }
+            if hits.is_empty() {
+                continue;
             }
+            // shielded ships bounce off what would have destroyed them
+            if ship.absorb_crash() {
+                for (contact, normal) in hits {
+                    Wall::bounce(ship.object_mut(), contact, normal);
+                }
+            } else {
+                crashed.push(i);
+            }
+        }
+        for i in crashed {
+            self.respawn_ship(i);
         }
     }
 
-    fn get_ship_index(&self, id: u8) -> Option<usize> {
-        self.ships.iter().position(|x| x.get_id() == id)
+    /// Pulls the ends of taut tethers together and snaps those pulled too hard.
+    fn update_tethers(&mut self, time: f64) {
+        let Some(config) = self.ship_config.tether else {
+            self.tethers.clear();
+            return;
+        };
+        let forces: Vec<Option<DVec2>> = self
+            .tethers
+            .iter()
+            .map(|tether| {
+                let from = self.ship(tether.owner)?;
+                tether.force(&config, from, self.target(tether.target)?)
+            })
+            .collect();
+        let mut kept = vec![];
+        for (tether, force) in self.tethers.iter().zip(forces) {
+            let Some(force) = force else {
+                continue;
+            };
+            kept.push(*tether);
+            // the force acts over the step like a field would
+            let pull = |object: &mut Object, force: DVec2, mass: f64| {
+                if mass > 0. {
+                    object.velocity += force / mass * time;
+                }
+            };
+            if let Some(index) = self.get_ship_index(tether.owner) {
+                let ship = &mut self.ships[index];
+                let mass = ship.object().mass * ship.buffs().mass_factor();
+                pull(ship.object_mut(), force, mass);
+            }
+            match tether.target {
+                Target::Planet(index) if self.planets[index].is_dynamic() => {
+                    let planet = &mut self.planets[index];
+                    let mass = planet.object().mass;
+                    pull(planet.object_mut(), -force, mass);
+                }
+                Target::Planet(_) => {}
+                Target::Ship(id) => {
+                    if let Some(index) = self.get_ship_index(id) {
+                        let ship = &mut self.ships[index];
+                        let mass = ship.object().mass * ship.buffs().mass_factor();
+                        pull(ship.object_mut(), -force, mass);
+                    }
+                }
+            }
+        }
+        self.tethers = kept;
     }
 
-    #[cfg(test)]
-    fn add_planet(&mut self, location: DVec2, mass: f64, field: f64, radius: f64, velocity: DVec2) {
+    fn respawn_ship(&mut self, index: usize) {
+        let id = self.ships[index].get_id();
+        let location = self.gen_spawn_location(Some(id));
+        self.ships[index].respawn(location);
+        self.release_tethers(id);
+    }
+
+    /// Expires anchors and detonates mines on ships close to them.
+    fn update_deployables(&mut self, time: f64) {
+        for deployable in self.deployables.iter_mut() {
+            deployable.update(time, DVec2::ZERO);
+        }
+        if let Some(config) = self.ship_config.anchors {
+            self.deployables.retain(|deployable| {
+                deployable.kind != DeployKind::Anchor || deployable.age() < config.seconds
+            });
+        }
+        let Some(config) = self.ship_config.mines else {
+            return;
+        };
+        let mut hit: Vec<usize> = vec![];
+        let ships = &self.ships;
+        self.deployables.retain(|mine| {
+            let hits: Vec<usize> = (0..ships.len())
+                .filter(|&i| mine.triggered_by(&ships[i], &config))
+                .collect();
+            hit.extend(&hits);
+            hits.is_empty()
+        });
+        hit.sort_unstable();
+        hit.dedup();
+        for i in hit {
+            if !self.ships[i].absorb_crash() {
+                self.respawn_ship(i);
+            }
+        }
+    }
+
+    /// Spawns pickups on the timers of their spawners, moves them and lets ships collect them.
+    fn update_pickups(&mut self, time: f64) {
+        for index in 0..self.server.spawners.len() {
+            self.server.spawn_timers[index] += time;
+            let spawner = &self.server.spawners[index];
+            if self.server.spawn_timers[index] < spawner.interval {
+                continue;
+            }
+            self.server.spawn_timers[index] = 0.;
+            let count = self.pickups.iter().filter(|p| p.spawner == index).count();
+            if count < spawner.max {
+                let location = match spawner.location {
+                    Some(location) => location,
+                    None => self.gen_pickup_location(spawner.radius),
+                };
+                let pickup = Pickup::new(index, &self.server.spawners[index], location);
+                self.pickups.push(pickup);
+            }
+        }
+        for pickup in self.pickups.iter_mut() {
+            let total_field = self
+                .planets
+                .iter()
+                .map(|other| pickup.get_field(other))
+                .sum();
+            pickup.update(time, total_field);
+        }
+        let planets = &self.planets;
+        self.pickups
+            .retain(|pickup| !planets.iter().any(|planet| planet.destroys(pickup)));
+        let ships = &mut self.ships;
+        self.pickups.retain(
+            |pickup| match ships.iter_mut().find(|ship| ship.collision(pickup)) {
+                Some(ship) => {
+                    ship.collect(pickup.kind);
+                    false
+                }
+                None => true,
+            },
+        );
+    }
+
+    /// Locations and radii of everything a new object should keep away from.
+    fn obstacles(&self, respawned_id: Option<u8>) -> Vec<(DVec2, f64)> {
         self.planets
-            .push(Planet::new(location, mass, field, radius, velocity));
+            .iter()
+            .map(|planet| (planet.object().location, planet.danger_radius()))
+            .chain(
+                self.ships
+                    .iter()
+                    .filter(|ship| Some(ship.get_id()) != respawned_id)
+                    .map(|ship| (ship.object().location, ship.object().radius)),
+            )
+            .chain(
+                self.pickups
+                    .iter()
+                    .map(|pickup| (pickup.object().location, pickup.object().radius)),
+            )
+            .collect()
+    }
+
+    fn gen_pickup_location(&mut self, radius: f64) -> DVec2 {
+        let obstacles = self.obstacles(None);
+        SpawnConfig::default().find_location(
+            &mut self.server.rng,
+            radius,
+            &obstacles,
+            &self.walls,
+            |_| DVec2::ZERO,
+        )
     }
-}
 
-pub fn gen_new_location() -> DVec2 {
-    let mut rng = rand::thread_rng();
-    DVec2::new(rng.gen_range(0. ..1.), rng.gen_range(0. ..1.))
+    fn gen_spawn_location(&mut self, respawned_id: Option<u8>) -> DVec2 {
+        let obstacles = self.obstacles(respawned_id);
+        let mut probe = Ship::new(0, DVec2::ZERO, &self.ship_config);
+        let planets = &self.planets;
+        self.spawn.find_location(
+            &mut self.server.rng,
+            self.ship_config.radius,
+            &obstacles,
+            &self.walls,
+            |location| {
+                probe.object_mut().location = location;
+                planets.iter().map(|planet| probe.get_field(planet)).sum()
+            },
+        )
+    }
+
+    fn get_ship_index(&self, id: u8) -> Option<usize> {
+        self.ships.iter().position(|x| x.get_id() == id)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::logic::pickup::PickupKind;
 
     fn basic_space() -> Space {
         let ship_config = ShipConfig {
@@ -129,6 +835,7 @@ mod tests {
             radius: 0.1,
             mass: 2.,
             field: 1.,
+            ..Default::default()
         };
         Space::new(ship_config)
     }
@@ -197,5 +904,104 @@ mod tests {
         assert_eq!(1, space.ships.len());
         let (_, velocity, _) = space.ships[0].get_motion();
         assert_eq!(DVec2::ZERO, velocity);
+        assert_eq!(Some(1), space.deaths(1));
+    }
+
+    #[test]
+    fn get_ship_by_id() {
+        let mut space = basic_space();
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.add_ship(2, DVec2::ZERO);
+        assert_eq!(DVec2::new(0.5, 0.5), space.ship(1).unwrap().location);
+        assert!(space.ship(3).is_none());
+        assert_eq!(2, space.ships().count());
+    }
+
+    #[test]
+    fn same_seed_same_simulation() {
+        let simulate = |seed| {
+            let mut space = basic_space();
+            space.seed(seed);
+            space.add_planet(DVec2::new(0.5, 0.5), 1., 0.1, 0.2, DVec2::ZERO);
+            for id in 1..=4 {
+                let location = space.gen_new_location();
+                space.add_ship(id, location);
+                space.move_ship(id, Some(f64::from(id)));
+            }
+            for _ in 0..1000 {
+                space.update(0.01);
+            }
+            space.get_state_binary()
+        };
+        assert_eq!(simulate(42), simulate(42));
+        assert_ne!(simulate(42), simulate(43));
+    }
+
+    #[test]
+    fn clients_do_not_receive_server_state() {
+        let mut space = basic_space();
+        space.seed(7);
+        let view: Space = bincode::deserialize(&space.get_state_binary()).unwrap();
+        assert_eq!(None, view.get_seed());
+        let full: FullState =
+            bincode::deserialize(&bincode::serialize(&FullState(space.clone())).unwrap()).unwrap();
+        assert_eq!(Some(7), full.0.get_seed());
+        assert_eq!(space.gen_new_location(), full.0.clone().gen_new_location());
+    }
+
+    #[test]
+    fn clear_input_stops_thrust() {
+        let mut space = basic_space();
+        space.add_ship(1, DVec2::new(0.5, 0.5));
+        space.move_ship(1, Some(0.));
+        space.steer_ship(
+            1,
+            Steering {
+                thrust: 1.,
+                turn: 1.,
+            },
+        );
+        space.clear_input();
+        space.update(0.1);
+        assert_eq!(DVec2::ZERO, space.ship(1).unwrap().velocity);
+    }
+
+    #[test]
+    fn replace_map_keeps_ships() {
+        let mut space = basic_space();
+        space.add_ship(3, DVec2::ZERO);
+        space.update_ships(0.);
+        space.ships[0].collect(PickupKind::Shield { seconds: 10. });
+        let mut map = basic_space();
+        map.ship_config.radius = 0.05;
+        map.add_planet(DVec2::new(0.5, 0.5), 0., 0.1, 0.1, DVec2::ZERO);
+        space.replace_map(map);
+        assert_eq!(1, space.planets().count());
+        assert_eq!(0.05, space.ship(3).unwrap().radius);
+        assert_eq!(Some(0), space.deaths(3));
+        assert!(!space.ships[0].buffs().shielded());
+    }
+
+    #[test]
+    fn remove_planet_out_of_range() {
+        let mut space = basic_space();
+        space.add_planet(DVec2::ZERO, 0., 0.1, 0.1, DVec2::ZERO);
+        assert!(!space.remove_planet(1));
+        assert!(space.remove_planet(0));
+        assert_eq!(0, space.planets().count());
+    }
+
+    #[test]
+    fn removing_planet_keeps_wormhole_exits() {
+        let mut space = basic_space();
+        for x in [0.2, 0.4, 0.6] {
+            space.add_planet(DVec2::new(x, 0.5), 0., 0., 0.05, DVec2::ZERO);
+        }
+        space.set_planet_kind(0, BodyKind::Wormhole { exit: 2 });
+        space.set_planet_kind(2, BodyKind::Wormhole { exit: 0 });
+        space.remove_planet(1);
+        assert_eq!(&BodyKind::Wormhole { exit: 1 }, space.planets[0].kind());
+        space.remove_planet(0);
+        assert_eq!(&BodyKind::Planet, space.planets[0].kind());
     }
 }
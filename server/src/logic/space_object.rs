@@ -3,15 +3,38 @@ use serde::{Deserialize, Serialize};
 
 use crate::ui::display::{DisplayInfo, DisplayType};
 
+use super::body::BodyKind;
+use super::control::{self, ControlModel, Steering};
+use super::fuel::FuelConfig;
+use super::motion::Motion;
 use super::object::{Object, Update};
+use super::pickup::{Buffs, PickupKind};
 use super::space::ShipConfig;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Planet {
     object: Object,
+    #[serde(default)]
+    motion: Motion,
+    /// Seconds on its path
+    #[serde(default)]
+    time: f64,
+    #[serde(default)]
+    kind: BodyKind,
 }
 
 impl Update for Planet {
+    fn update(&mut self, time: f64, total_field: DVec2) {
+        match self.motion {
+            Motion::Dynamic => self.object.update(time, total_field),
+            Motion::Anchored => {}
+            _ => {
+                self.time += time;
+                self.follow_path();
+            }
+        }
+    }
+
     fn object(&self) -> &Object {
         &self.object
     }
@@ -22,44 +45,113 @@ impl Update for Planet {
 }
 
 impl Planet {
-    #[cfg(test)]
     pub fn new(location: DVec2, mass: f64, field: f64, radius: f64, velocity: DVec2) -> Planet {
         Planet {
             object: Object::new(location, radius, mass, field, velocity),
+            motion: Motion::Dynamic,
+            time: 0.,
+            kind: BodyKind::Planet,
+        }
+    }
+
+    pub fn kind(&self) -> &BodyKind {
+        &self.kind
+    }
+
+    /// Repulsors get a negative field of the same strength.
+    pub fn set_kind(&mut self, kind: BodyKind) {
+        if kind == BodyKind::Repulsor {
+            self.object.field = -self.object.field.abs();
+        }
+        self.kind = kind;
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.motion.is_dynamic()
+    }
+
+    /// Distance from the center within which ships are destroyed.
+    pub fn danger_radius(&self) -> f64 {
+        match self.kind {
+            BodyKind::BlackHole { horizon } => horizon.max(self.object.radius),
+            _ => self.object.radius,
+        }
+    }
+
+    pub fn destroys(&self, other: &impl Update) -> bool {
+        match self.kind {
+            BodyKind::Planet | BodyKind::Repulsor => other.collision(self),
+            BodyKind::BlackHole { .. } => {
+                let object = other.object();
+                self.object.location.distance(object.location)
+                    <= self.danger_radius() + object.radius
+            }
+            BodyKind::Wormhole { .. } => false,
+        }
+    }
+
+    /// Puts the planet on rails, starting from the beginning of its path.
+    pub fn set_motion(&mut self, motion: Motion) {
+        self.motion = motion;
+        self.time = 0.;
+        if !self.motion.is_dynamic() {
+            self.object.velocity = DVec2::ZERO;
+            self.object.acceleration = DVec2::ZERO;
+        }
+        self.follow_path();
+    }
+
+    fn follow_path(&mut self) {
+        if let Some((location, velocity)) = self.motion.state(self.time) {
+            self.object.location = location;
+            self.object.velocity = velocity;
         }
     }
 
     pub fn get_display_info(&self) -> DisplayInfo {
         DisplayInfo {
-            display_type: DisplayType::Planet,
+            display_type: match self.kind {
+                BodyKind::Planet => DisplayType::Planet,
+                BodyKind::BlackHole { .. } => DisplayType::BlackHole,
+                BodyKind::Repulsor => DisplayType::Repulsor,
+                BodyKind::Wormhole { .. } => DisplayType::Wormhole,
+            },
             id: None,
             x: self.object.location.x,
             y: self.object.location.y,
-            radius: self.object.radius,
+            radius: self.danger_radius(),
+            heading: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ship {
     id: u8,
     object: Object,
     direction: Option<f64>,
     force: f64,
+    #[serde(default)]
+    deaths: u32,
+    #[serde(default)]
+    controls: ControlModel,
+    /// Angle the ship faces
+    #[serde(default)]
+    heading: f64,
+    /// Angular speed under rotational control
+    #[serde(default)]
+    spin: f64,
+    #[serde(default)]
+    steering: Steering,
+    #[serde(default)]
+    fuel: Option<FuelConfig>,
+    #[serde(default)]
+    energy: f64,
+    #[serde(default)]
+    buffs: Buffs,
 }
 
 impl Update for Ship {
-    fn update(&mut self, time: f64, total_field: DVec2) {
-        if let Some(direction) = self.direction {
-            self.object.update(
-                time,
-                total_field + self.force * DVec2::from_angle(direction),
-            );
-        } else {
-            self.object.update(time, total_field);
-        }
-    }
-
     fn object(&self) -> &Object {
         &self.object
     }
@@ -82,6 +174,106 @@ impl Ship {
             ),
             direction: None,
             force: ship_config.force,
+            deaths: 0,
+            controls: ship_config.controls,
+            heading: 0.,
+            spin: 0.,
+            steering: Steering::default(),
+            fuel: ship_config.fuel,
+            energy: ship_config.fuel.map_or(0., |fuel| fuel.capacity),
+            buffs: Buffs::default(),
+        }
+    }
+
+    pub fn thrust(&self) -> DVec2 {
+        match self.controls {
+            ControlModel::Direct => match self.direction {
+                Some(direction) => self.force * DVec2::from_angle(direction),
+                None => DVec2::ZERO,
+            },
+            ControlModel::Rotational { reverse, .. } => {
+                let thrust = self.steering.thrust.clamp(-1., 1.);
+                let force = match thrust < 0. {
+                    true => self.force * reverse,
+                    false => self.force,
+                };
+                thrust * force * DVec2::from_angle(self.heading)
+            }
+        }
+    }
+
+    /// Burns energy for `thrust` over `time` seconds, returns the thrust it allows.
+    pub fn burn(&mut self, thrust: DVec2, time: f64) -> DVec2 {
+        match self.fuel {
+            Some(fuel) => {
+                let (thrust, energy) = fuel.burn(self.energy, thrust, time);
+                self.energy = energy;
+                thrust
+            }
+            None => thrust,
+        }
+    }
+
+    /// Spends the energy `cost` picks from the fuel config, returns false without enough.
+    /// Ships with unlimited fuel can always afford it.
+    pub fn spend(&mut self, cost: impl Fn(&FuelConfig) -> f64) -> bool {
+        let Some(fuel) = self.fuel else {
+            return true;
+        };
+        match fuel.spend(self.energy, cost(&fuel)) {
+            Some(energy) => {
+                self.energy = energy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether a shield saves the ship from a crash, spending its energy.
+    pub fn absorb_crash(&mut self) -> bool {
+        self.buffs.shielded() && self.spend(|fuel| fuel.shield_cost)
+    }
+
+    pub fn regenerate(&mut self, field_strength: f64, bonus: f64, time: f64) {
+        if let Some(fuel) = self.fuel {
+            self.energy = fuel.regenerate(self.energy, field_strength, bonus, time);
+        }
+    }
+
+    pub fn collect(&mut self, kind: PickupKind) {
+        if let (PickupKind::Energy { amount }, Some(fuel)) = (kind, self.fuel) {
+            self.energy = (self.energy + amount).min(fuel.capacity);
+        }
+        self.buffs.add(kind);
+    }
+
+    pub fn buffs(&self) -> &Buffs {
+        &self.buffs
+    }
+
+    pub fn buffs_mut(&mut self) -> &mut Buffs {
+        &mut self.buffs
+    }
+
+    /// Energy left and capacity of ships with limited fuel.
+    pub fn fuel_gauge(&self) -> Option<(f64, f64)> {
+        self.fuel.map(|fuel| (self.energy, fuel.capacity))
+    }
+
+    /// Turns the ship under rotational control.
+    pub fn rotate(&mut self, time: f64) {
+        if let ControlModel::Rotational {
+            torque, max_spin, ..
+        } = self.controls
+        {
+            (self.heading, self.spin) = control::rotate(
+                self.heading,
+                self.spin,
+                self.steering,
+                torque,
+                max_spin,
+                time,
+            );
         }
     }
 
@@ -92,13 +284,34 @@ impl Ship {
             x: self.object.location.x,
             y: self.object.location.y,
             radius: self.object.radius,
+            heading: Some(self.heading),
         }
     }
 
+    pub fn reconfigure(&mut self, new_location: DVec2, ship_config: &ShipConfig) {
+        self.object = Object::new(
+            new_location,
+            ship_config.radius,
+            ship_config.mass,
+            ship_config.field,
+            DVec2::ZERO,
+        );
+        self.force = ship_config.force;
+        self.controls = ship_config.controls;
+        self.spin = 0.;
+        self.fuel = ship_config.fuel;
+        self.energy = ship_config.fuel.map_or(0., |fuel| fuel.capacity);
+        self.buffs.clear();
+    }
+
     pub fn respawn(&mut self, new_location: DVec2) {
         self.object.location = new_location;
         self.object.velocity = DVec2::ZERO;
         self.object.acceleration = DVec2::ZERO;
+        self.spin = 0.;
+        self.energy = self.fuel.map_or(0., |fuel| fuel.capacity);
+        self.buffs.clear();
+        self.deaths += 1;
     }
 
     #[cfg(test)]
@@ -114,7 +327,27 @@ impl Ship {
         self.id
     }
 
+    pub fn get_deaths(&self) -> u32 {
+        self.deaths
+    }
+
+    pub fn heading(&self) -> f64 {
+        self.heading
+    }
+
+    pub fn spin(&self) -> f64 {
+        self.spin
+    }
+
+    /// Thrust direction under direct control, the ship turns to face it.
     pub fn change_direction(&mut self, direction: Option<f64>) {
         self.direction = direction;
+        if let (ControlModel::Direct, Some(direction)) = (self.controls, direction) {
+            self.heading = direction;
+        }
+    }
+
+    pub fn steer(&mut self, steering: Steering) {
+        self.steering = steering;
     }
 }
@@ -0,0 +1,207 @@
+use glam::DVec2;
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::wall::Wall;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum SpawnArea {
+    Point(#[schemars(with = "[f64; 2]")] DVec2),
+    Rect {
+        #[schemars(with = "[f64; 2]")]
+        min: DVec2,
+        #[schemars(with = "[f64; 2]")]
+        max: DVec2,
+    },
+    Circle {
+        #[schemars(with = "[f64; 2]")]
+        center: DVec2,
+        radius: f64,
+    },
+}
+
+impl SpawnArea {
+    fn sample(&self, rng: &mut impl Rng) -> DVec2 {
+        match *self {
+            SpawnArea::Point(point) => point,
+            SpawnArea::Rect { min, max } => {
+                DVec2::new(rng.gen_range(min.x..=max.x), rng.gen_range(min.y..=max.y))
+            }
+            SpawnArea::Circle { center, radius } => {
+                let angle = rng.gen_range(0. ..std::f64::consts::TAU);
+                // sqrt for uniform density over the disc
+                let distance = radius * rng.gen_range(0. ..=1f64).sqrt();
+                center + distance * DVec2::from_angle(angle)
+            }
+        }
+    }
+}
+
+/// Named location ships spawn at.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnPoint {
+    pub name: String,
+    #[schemars(with = "[f64; 2]")]
+    pub location: DVec2,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnConfig {
+    /// Areas to spawn in, the whole map if empty
+    #[serde(default)]
+    pub areas: Vec<SpawnArea>,
+    /// Minimal free distance between a spawned ship and other bodies
+    #[serde(default = "default_margin")]
+    pub margin: f64,
+    /// Maximal field strength at a spawn location
+    #[serde(default)]
+    pub max_field: Option<f64>,
+    #[serde(default = "default_attempts")]
+    pub attempts: usize,
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        SpawnConfig {
+            areas: vec![],
+            margin: default_margin(),
+            max_field: None,
+            attempts: default_attempts(),
+        }
+    }
+}
+
+fn default_margin() -> f64 {
+    0.05
+}
+
+fn default_attempts() -> usize {
+    32
+}
+
+impl SpawnConfig {
+    /// Picks a location for a ship of `radius` away from `obstacles` (location, radius)
+    /// and `walls` and out of strong `field`. When no candidate is safe, the one
+    /// furthest from any obstacle is used.
+    pub fn find_location(
+        &self,
+        rng: &mut impl Rng,
+        radius: f64,
+        obstacles: &[(DVec2, f64)],
+        walls: &[Wall],
+        mut field: impl FnMut(DVec2) -> DVec2,
+    ) -> DVec2 {
+        let mut best = (DVec2::ZERO, f64::NEG_INFINITY);
+        for _ in 0..self.attempts.max(1) {
+            let location = self.sample(rng);
+            let clearance = obstacles
+                .iter()
+                .map(|&(other, other_radius)| location.distance(other) - other_radius - radius)
+                .chain(walls.iter().map(|wall| wall.distance(location) - radius))
+                .fold(f64::INFINITY, f64::min);
+            let calm = self
+                .max_field
+                .is_none_or(|max_field| field(location).length() <= max_field);
+            if clearance >= self.margin && calm {
+                return location;
+            }
+            if clearance > best.1 {
+                best = (location, clearance);
+            }
+        }
+        best.0
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> DVec2 {
+        if self.areas.is_empty() {
+            return DVec2::new(rng.gen_range(0. ..1.), rng.gen_range(0. ..1.));
+        }
+        let area = &self.areas[rng.gen_range(0..self.areas.len())];
+        area.sample(rng).clamp(DVec2::ZERO, DVec2::ONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::wall::{Hit, WallShape};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn rng() -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(0)
+    }
+
+    #[test]
+    fn spawn_at_point() {
+        let config = SpawnConfig {
+            areas: vec![SpawnArea::Point(DVec2::new(0.3, 0.4))],
+            ..Default::default()
+        };
+        let location = config.find_location(&mut rng(), 0.01, &[], &[], |_| DVec2::ZERO);
+        assert_eq!(DVec2::new(0.3, 0.4), location);
+    }
+
+    #[test]
+    fn spawn_away_from_obstacles() {
+        let config = SpawnConfig::default();
+        let obstacles = [(DVec2::new(0.25, 0.5), 0.25)];
+        let mut rng = rng();
+        for _ in 0..100 {
+            let location = config.find_location(&mut rng, 0.01, &obstacles, &[], |_| DVec2::ZERO);
+            assert!(location.distance(obstacles[0].0) >= 0.25 + 0.01 + config.margin);
+        }
+    }
+
+    #[test]
+    fn spawn_away_from_strong_field() {
+        let config = SpawnConfig {
+            max_field: Some(1.),
+            ..Default::default()
+        };
+        let field = |location: DVec2| DVec2::new(if location.x < 0.5 { 10. } else { 0. }, 0.);
+        let mut rng = rng();
+        for _ in 0..100 {
+            assert!(config.find_location(&mut rng, 0.01, &[], &[], field).x >= 0.5);
+        }
+    }
+
+    #[test]
+    fn spawn_away_from_walls() {
+        let config = SpawnConfig::default();
+        let walls = [Wall {
+            shape: WallShape::Polygon {
+                points: vec![
+                    DVec2::ZERO,
+                    DVec2::new(0.5, 0.),
+                    DVec2::new(0.5, 1.),
+                    DVec2::Y,
+                ],
+            },
+            hit: Hit::Bounce,
+        }];
+        let mut rng = rng();
+        for _ in 0..100 {
+            let location = config.find_location(&mut rng, 0.01, &[], &walls, |_| DVec2::ZERO);
+            assert!(location.x >= 0.5 + 0.01 + config.margin);
+        }
+    }
+
+    #[test]
+    fn spawn_in_crowded_map_picks_most_free_location() {
+        let config = SpawnConfig {
+            areas: vec![
+                SpawnArea::Point(DVec2::new(0.1, 0.1)),
+                SpawnArea::Point(DVec2::new(0.9, 0.9)),
+            ],
+            ..Default::default()
+        };
+        let obstacles = [(DVec2::new(0.2, 0.2), 0.5), (DVec2::new(0.9, 0.9), 0.1)];
+        let location = config.find_location(&mut rng(), 0.01, &obstacles, &[], |_| DVec2::ZERO);
+        assert_eq!(DVec2::new(0.9, 0.9), location);
+    }
+}
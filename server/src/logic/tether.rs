@@ -0,0 +1,152 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::object::Object;
+
+/// Tethers ships can latch onto planets and other ships with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TetherConfig {
+    /// Largest gap between the surfaces to latch on
+    pub range: f64,
+    /// Force per unit of stretch beyond the length at latching
+    pub stiffness: f64,
+    /// Force per unit of speed the ends move apart with
+    #[serde(default)]
+    pub damping: f64,
+    /// Force at which the tether snaps
+    pub break_force: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    /// Index of the planet
+    Planet(usize),
+    Ship(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tether {
+    pub owner: u8,
+    pub target: Target,
+    /// Distance between the centers the tether goes slack below
+    pub length: f64,
+}
+
+impl Tether {
+    /// Force pulling `from` towards `to`, `None` if it exceeds the break force.
+    /// Like a rope it only pulls while stretched beyond its length.
+    pub fn force(&self, config: &TetherConfig, from: &Object, to: &Object) -> Option<DVec2> {
+        let offset = to.location - from.location;
+        let distance = offset.length();
+        let stretch = distance - self.length;
+        if stretch <= 0. || distance == 0. {
+            return Some(DVec2::ZERO);
+        }
+        let direction = offset / distance;
+        let separating = (to.velocity - from.velocity).dot(direction);
+        let magnitude = (config.stiffness * stretch + config.damping * separating).max(0.);
+        (magnitude <= config.break_force).then_some(direction * magnitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{
+        object::Update,
+        space::{ShipConfig, Space},
+    };
+
+    fn tether_space() -> Space {
+        Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.01,
+            mass: 2.,
+            field: 1.,
+            tether: Some(TetherConfig {
+                range: 0.1,
+                stiffness: 10.,
+                damping: 0.,
+                break_force: 1.,
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn pulls_when_stretched_and_snaps() {
+        let config = TetherConfig {
+            range: 0.1,
+            stiffness: 10.,
+            damping: 1.,
+            break_force: 2.,
+        };
+        let tether = Tether {
+            owner: 1,
+            target: Target::Planet(0),
+            length: 0.2,
+        };
+        let from = Object::new(DVec2::new(0.1, 0.5), 0.01, 1., 0., DVec2::ZERO);
+        let slack = Object::new(DVec2::new(0.2, 0.5), 0.01, 1., 0., DVec2::ZERO);
+        let taut = Object::new(DVec2::new(0.4, 0.5), 0.01, 1., 0., DVec2::new(0.5, 0.));
+        let far = Object::new(DVec2::new(0.6, 0.5), 0.01, 1., 0., DVec2::ZERO);
+        assert_eq!(Some(DVec2::ZERO), tether.force(&config, &from, &slack));
+        let force = tether.force(&config, &from, &taut).unwrap();
+        assert!((force - DVec2::new(1.5, 0.)).length() < 1e-9);
+        assert_eq!(None, tether.force(&config, &from, &far));
+    }
+
+    #[test]
+    fn tether_latches_onto_closest_in_range() {
+        let mut space = tether_space();
+        space.add_planet(DVec2::new(0.2, 0.5), 1., 0., 0.05, DVec2::ZERO);
+        space.add_planet(DVec2::new(0.5, 0.5), 1., 0., 0.05, DVec2::ZERO);
+        space.add_ship(1, DVec2::new(0.4, 0.5));
+        space.add_ship(2, DVec2::new(0.8, 0.5));
+        space.latch_tether(1);
+        let tether = *space.tethers().next().unwrap();
+        assert_eq!(Target::Planet(1), tether.target);
+        assert!((tether.length - 0.1).abs() < 1e-9);
+        // repeated latching keeps the tether
+        space.latch_tether(1);
+        assert_eq!(vec![tether], space.tethers().copied().collect::<Vec<_>>());
+        space.release_tether(1);
+        space.release_tether(1);
+        assert_eq!(0, space.tethers().count());
+        // nothing in range
+        space.latch_tether(2);
+        assert_eq!(0, space.tethers().count());
+
+        space.latch_tether(1);
+        space.remove_planet(0);
+        assert_eq!(Target::Planet(0), space.tethers().next().unwrap().target);
+        space.remove_planet(0);
+        assert_eq!(0, space.tethers().count());
+    }
+
+    #[test]
+    fn tether_pulls_ships_together_until_it_snaps() {
+        let mut space = tether_space();
+        space.add_ship(1, DVec2::new(0.4, 0.5));
+        space.add_ship(2, DVec2::new(0.45, 0.5));
+        space.latch_tether(1);
+        space.ship_mut(2).unwrap().object_mut().velocity = DVec2::new(0.5, 0.);
+        space.update(0.02);
+        space.update(0.02);
+        // both ends are pulled towards each other
+        let first = space.ship(1).unwrap().velocity.x;
+        assert!(first > 0.);
+        assert!(space.ship(2).unwrap().velocity.x < 0.5);
+        assert_eq!(1, space.tethers().count());
+
+        space.ship_mut(2).unwrap().object_mut().location = DVec2::new(0.9, 0.5);
+        space.update(0.02);
+        assert_eq!(0, space.tethers().count());
+        // removed ships take their tethers with them
+        space.latch_tether(1);
+        space.remove_ship(1);
+        assert_eq!(0, space.tethers().count());
+    }
+}
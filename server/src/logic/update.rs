@@ -3,29 +3,81 @@ use std::sync::{mpsc::Receiver, Arc, Mutex};
 use std::time::Duration;
 
 use crate::constants;
-use crate::ui::command;
+use crate::ui::admin;
+use crate::ui::command::{self, Command};
 
+use super::replay::ReplayRecorder;
 use super::space::Space;
 
-pub fn run_game(space_counter: &Arc<Mutex<Space>>, command_receiver: Receiver<Vec<u8>>) {
+/// Real time pacing of the game threads. The simulated time step stays
+/// `GAME_UPDATE_TICK_SECONDS` so changing the pace keeps replays valid.
+pub struct Clock {
+    pub paused: bool,
+    pub update_seconds: f64,
+    pub state_seconds: f64,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            paused: false,
+            update_seconds: constants::GAME_UPDATE_TICK_SECONDS,
+            state_seconds: constants::GAME_STATE_TICK_SECONDS,
+        }
+    }
+}
+
+pub fn run_game(
+    space_counter: &Arc<Mutex<Space>>,
+    clock: &Arc<Mutex<Clock>>,
+    command_receiver: Receiver<Command>,
+    recorder: Option<ReplayRecorder>,
+) {
     loop {
-        std::thread::sleep(Duration::from_secs_f64(constants::GAME_UPDATE_TICK_SECONDS));
+        let update_seconds = clock.lock().unwrap().update_seconds;
+        std::thread::sleep(Duration::from_secs_f64(update_seconds));
         let mut space = space_counter.lock().unwrap();
+        let mut commands = vec![];
         for command in command_receiver.try_iter() {
-            command::run_command(&mut space, command);
+            match command {
+                Command::Player(command) => {
+                    command::run_command(&mut space, &command);
+                    commands.push(command);
+                }
+                Command::Admin(command) => {
+                    let changed_space =
+                        admin::run_admin_command(&mut space, &mut clock.lock().unwrap(), command);
+                    if let (true, Some(recorder)) = (changed_space, &recorder) {
+                        // replays skip the commands recorded before a snapshot of their tick
+                        recorder.record_commands(space.get_tick(), std::mem::take(&mut commands));
+                        recorder.record_snapshot(&space);
+                    }
+                }
+            }
+        }
+        if let Some(recorder) = &recorder {
+            recorder.record_commands(space.get_tick(), commands);
+        }
+        if clock.lock().unwrap().paused {
+            continue;
         }
         space.update(constants::GAME_UPDATE_TICK_SECONDS);
+        if let Some(recorder) = &recorder {
+            recorder.record_keyframe(&space);
+        }
     }
 }
 
-pub fn run_state_send(space_counter: &Arc<Mutex<Space>>, state_sender: &Arc<Mutex<Bus<Vec<u8>>>>) {
+pub fn run_state_send(
+    space_counter: &Arc<Mutex<Space>>,
+    clock: &Arc<Mutex<Clock>>,
+    state_sender: &Arc<Mutex<Bus<Arc<Space>>>>,
+) {
     loop {
-        let state;
-        {
-            let space: std::sync::MutexGuard<'_, Space> = space_counter.lock().unwrap();
-            state = space.get_state_binary();
-        }
+        // players get their own view of the state
+        let state = Arc::new(space_counter.lock().unwrap().clone());
         state_sender.lock().unwrap().broadcast(state);
-        std::thread::sleep(Duration::from_secs_f64(constants::GAME_STATE_TICK_SECONDS));
+        let state_seconds = clock.lock().unwrap().state_seconds;
+        std::thread::sleep(Duration::from_secs_f64(state_seconds));
     }
 }
@@ -0,0 +1,824 @@
+use glam::DVec2;
+use std::fmt;
+
+use crate::constants;
+
+use super::control::ControlModel;
+use super::field::FieldLaw;
+use super::map::{Map, PlanetConfig, PlanetKind};
+use super::motion::Motion;
+use super::pickup::{PickupKind, Spawner};
+use super::spawn::SpawnArea;
+use super::wall::{Wall, WallShape};
+use super::zone::{Effect, Shape, Zone};
+
+/// Problem in a map, located by the JSON path of the offending value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// All problems found in a map, one per line.
+#[derive(Debug)]
+pub struct MapErrors(pub Vec<MapError>);
+
+impl fmt::Display for MapErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.0.iter().map(|error| error.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for MapErrors {}
+
+struct Validator {
+    errors: Vec<MapError>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.errors.push(MapError {
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn finite(&mut self, path: &str, value: f64) -> bool {
+        if !value.is_finite() {
+            self.error(path, format!("must be a finite number, found {value}"));
+        }
+        value.is_finite()
+    }
+
+    fn positive(&mut self, path: &str, value: f64) {
+        if self.finite(path, value) && value <= 0. {
+            self.error(path, format!("must be positive, found {value}"));
+        }
+    }
+
+    fn not_negative(&mut self, path: &str, value: f64) {
+        if self.finite(path, value) && value < 0. {
+            self.error(path, format!("must not be negative, found {value}"));
+        }
+    }
+
+    fn vector(&mut self, path: &str, value: DVec2) -> bool {
+        let x = self.finite(&format!("{path}[0]"), value.x);
+        let y = self.finite(&format!("{path}[1]"), value.y);
+        x && y
+    }
+
+    fn in_world(&mut self, path: &str, location: DVec2) {
+        let world = 0. ..=1.;
+        if self.vector(path, location)
+            && !(world.contains(&location.x) && world.contains(&location.y))
+        {
+            self.error(
+                path,
+                format!("[{}, {}] is outside of the world", location.x, location.y),
+            );
+        }
+    }
+
+    fn planet(&mut self, path: &str, planet: &PlanetConfig, location: DVec2) {
+        let on_path = planet.motion.state(0.).is_some();
+        match (&planet.orbit, planet.location) {
+            (Some(_), _) => self.in_world(&format!("{path}.orbit"), location),
+            (None, _) if on_path => {}
+            (None, Some(location)) => self.in_world(&format!("{path}.location"), location),
+            (None, None) => self.error(
+                &format!("{path}.location"),
+                "is required for planets without an orbit or path",
+            ),
+        }
+        self.motion(&format!("{path}.motion"), &planet.motion);
+        if planet.orbit.is_some() && !planet.motion.is_dynamic() {
+            self.error(
+                &format!("{path}.motion"),
+                "orbiting planets must be dynamic",
+            );
+        }
+        self.positive(&format!("{path}.radius"), planet.radius);
+        self.not_negative(&format!("{path}.mass"), planet.mass);
+        self.finite(&format!("{path}.field"), planet.field);
+        self.vector(&format!("{path}.velocity"), planet.velocity);
+    }
+
+    fn motion(&mut self, path: &str, motion: &Motion) {
+        match motion {
+            Motion::Dynamic | Motion::Anchored => {}
+            &Motion::Circle {
+                center,
+                radius,
+                period,
+                phase,
+                ..
+            } => {
+                self.positive(&format!("{path}.circle.radius"), radius);
+                self.positive(&format!("{path}.circle.period"), period);
+                self.finite(&format!("{path}.circle.phase"), phase);
+                let world = radius..=1. - radius;
+                if self.vector(&format!("{path}.circle.center"), center)
+                    && !(world.contains(&center.x) && world.contains(&center.y))
+                {
+                    self.error(&format!("{path}.circle"), "path leaves the world");
+                }
+            }
+            Motion::Waypoints { waypoints, repeat } => {
+                let path = format!("{path}.waypoints");
+                if waypoints.is_empty() {
+                    self.error(&format!("{path}.waypoints"), "must not be empty");
+                }
+                for (index, waypoint) in waypoints.iter().enumerate() {
+                    let path = format!("{path}.waypoints[{index}]");
+                    self.in_world(&format!("{path}.location"), waypoint.location);
+                    self.not_negative(&format!("{path}.time"), waypoint.time);
+                    if index > 0 && waypoint.time <= waypoints[index - 1].time {
+                        self.error(
+                            &format!("{path}.time"),
+                            "must be later than the previous waypoint",
+                        );
+                    }
+                }
+                if *repeat && waypoints.last().is_some_and(|last| last.time <= 0.) {
+                    self.error(
+                        &format!("{path}.repeat"),
+                        "repeated paths need a last waypoint after the start",
+                    );
+                }
+            }
+        }
+    }
+
+    fn kind(&mut self, path: &str, map: &Map, index: usize) {
+        let planet = &map.planets[index];
+        match &planet.kind {
+            PlanetKind::Planet => {}
+            &PlanetKind::BlackHole { horizon } => {
+                self.positive(&format!("{path}.kind.black_hole.horizon"), horizon)
+            }
+            PlanetKind::Repulsor => {
+                if planet.field <= 0. {
+                    self.error(
+                        &format!("{path}.field"),
+                        "must be positive for repulsors, which invert it",
+                    );
+                }
+            }
+            PlanetKind::Wormhole { exit } => {
+                let path = format!("{path}.kind.wormhole.exit");
+                match map.find_planet(exit, map.planets.len()) {
+                    None => self.error(&path, format!("no planet named '{exit}'")),
+                    Some(exit_index) if exit_index == index => {
+                        self.error(&path, "must be another planet")
+                    }
+                    Some(exit_index) => {
+                        if !matches!(map.planets[exit_index].kind, PlanetKind::Wormhole { .. }) {
+                            self.error(&path, format!("'{exit}' is not a wormhole"));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn field_law(&mut self, path: &str, law: FieldLaw) {
+        match law {
+            FieldLaw::InverseSquare | FieldLaw::InverseLinear => {}
+            FieldLaw::Plummer { softening } => {
+                self.positive(&format!("{path}.plummer.softening"), softening)
+            }
+            FieldLaw::Yukawa { range } => self.positive(&format!("{path}.yukawa.range"), range),
+            FieldLaw::ConstantRange { range } => {
+                self.positive(&format!("{path}.constant_range.range"), range)
+            }
+        }
+    }
+
+    fn orbit(&mut self, path: &str, map: &Map, index: usize) {
+        let planet = &map.planets[index];
+        let Some(orbit) = &planet.orbit else {
+            return;
+        };
+        self.positive(
+            &format!("{path}.orbit.semi_major_axis"),
+            orbit.semi_major_axis,
+        );
+        if self.finite(&format!("{path}.orbit.eccentricity"), orbit.eccentricity)
+            && !(0. ..1.).contains(&orbit.eccentricity)
+        {
+            self.error(
+                &format!("{path}.orbit.eccentricity"),
+                "must be at least 0 and below 1",
+            );
+        }
+        self.finite(&format!("{path}.orbit.periapsis"), orbit.periapsis);
+        self.finite(&format!("{path}.orbit.phase"), orbit.phase);
+        if planet.mass <= 0. {
+            self.error(
+                &format!("{path}.mass"),
+                "must be positive for orbiting planets",
+            );
+        }
+        let Some(parent) = map.find_planet(&orbit.parent, index) else {
+            self.error(
+                &format!("{path}.orbit.parent"),
+                format!("no planet named '{}' before this one", orbit.parent),
+            );
+            return;
+        };
+        let parent = &map.planets[parent];
+        if parent.field <= 0. || parent.kind == PlanetKind::Repulsor {
+            self.error(
+                &format!("{path}.orbit.parent"),
+                format!("'{}' has no attracting field", orbit.parent),
+            );
+        }
+        let valid_shape = orbit.semi_major_axis > 0. && (0. ..1.).contains(&orbit.eccentricity);
+        if valid_shape && orbit.periapsis_distance() <= parent.radius + planet.radius {
+            self.error(
+                &format!("{path}.orbit.semi_major_axis"),
+                format!("closest approach hits '{}'", orbit.parent),
+            );
+        }
+        let apoapsis = orbit.semi_major_axis * (1. + orbit.eccentricity);
+        let law = parent.field_law.unwrap_or(map.field_law);
+        if valid_shape
+            && parent.field > 0.
+            && law.strength(parent.field, parent.radius, apoapsis) <= 0.
+        {
+            self.error(
+                &format!("{path}.orbit.semi_major_axis"),
+                format!("'{}' has no field this far out", orbit.parent),
+            );
+        }
+    }
+
+    fn zone(&mut self, path: &str, zone: &Zone) {
+        match &zone.shape {
+            &Shape::Circle { center, radius } => {
+                self.vector(&format!("{path}.shape.circle.center"), center);
+                self.positive(&format!("{path}.shape.circle.radius"), radius);
+            }
+            Shape::Polygon { points } => {
+                if points.len() < 3 {
+                    self.error(
+                        &format!("{path}.shape.polygon.points"),
+                        "needs at least 3 points",
+                    );
+                }
+                for (index, &point) in points.iter().enumerate() {
+                    self.vector(&format!("{path}.shape.polygon.points[{index}]"), point);
+                }
+            }
+        }
+        if zone.effects.is_empty() {
+            self.error(&format!("{path}.effects"), "must not be empty");
+        }
+        for (index, effect) in zone.effects.iter().enumerate() {
+            let path = format!("{path}.effects[{index}]");
+            match *effect {
+                Effect::Drag { linear, quadratic } => {
+                    self.not_negative(&format!("{path}.drag.linear"), linear);
+                    self.not_negative(&format!("{path}.drag.quadratic"), quadratic);
+                }
+                Effect::Current { force } => {
+                    self.vector(&format!("{path}.current.force"), force);
+                }
+                Effect::Dampening { factor } => {
+                    self.not_negative(&format!("{path}.dampening.factor"), factor);
+                }
+                Effect::Blackout => {}
+                Effect::Refuel { rate } => {
+                    self.not_negative(&format!("{path}.refuel.rate"), rate);
+                }
+            }
+        }
+    }
+
+    fn wall(&mut self, path: &str, wall: &Wall) {
+        match &wall.shape {
+            &WallShape::Segment { from, to } => {
+                let valid = self.vector(&format!("{path}.shape.segment.from"), from)
+                    & self.vector(&format!("{path}.shape.segment.to"), to);
+                if valid && from == to {
+                    self.error(&format!("{path}.shape.segment"), "must have a length");
+                }
+            }
+            WallShape::Polygon { points } => {
+                let path = format!("{path}.shape.polygon.points");
+                let mut valid = true;
+                for (index, &point) in points.iter().enumerate() {
+                    valid &= self.vector(&format!("{path}[{index}]"), point);
+                }
+                if points.len() < 3 {
+                    self.error(&path, "needs at least 3 points");
+                } else if valid && !is_convex(points) {
+                    self.error(&path, "must be a convex polygon with the points in order");
+                }
+            }
+        }
+    }
+
+    fn pickup(&mut self, path: &str, spawner: &Spawner) {
+        match spawner.kind {
+            PickupKind::SpeedBoost { factor, seconds } => {
+                self.positive(&format!("{path}.kind.speed_boost.factor"), factor);
+                self.positive(&format!("{path}.kind.speed_boost.seconds"), seconds);
+            }
+            PickupKind::Shield { seconds } => {
+                self.positive(&format!("{path}.kind.shield.seconds"), seconds);
+            }
+            PickupKind::Energy { amount } => {
+                self.positive(&format!("{path}.kind.energy.amount"), amount);
+            }
+            PickupKind::GravityImmunity { seconds } => {
+                self.positive(&format!("{path}.kind.gravity_immunity.seconds"), seconds);
+            }
+            PickupKind::Mass { factor, seconds } => {
+                self.positive(&format!("{path}.kind.mass.factor"), factor);
+                self.positive(&format!("{path}.kind.mass.seconds"), seconds);
+            }
+        }
+        self.positive(&format!("{path}.interval"), spawner.interval);
+        self.positive(&format!("{path}.radius"), spawner.radius);
+        if spawner.max == 0 {
+            self.error(&format!("{path}.max"), "must be at least 1");
+        }
+        if let Some(location) = spawner.location {
+            self.in_world(&format!("{path}.location"), location);
+        }
+    }
+
+    fn spawn_area(&mut self, path: &str, area: &SpawnArea) {
+        match *area {
+            SpawnArea::Point(point) => self.in_world(&format!("{path}.point"), point),
+            SpawnArea::Rect { min, max } => {
+                self.in_world(&format!("{path}.rect.min"), min);
+                self.in_world(&format!("{path}.rect.max"), max);
+                if min.x > max.x || min.y > max.y {
+                    self.error(&format!("{path}.rect"), "min must not exceed max");
+                }
+            }
+            SpawnArea::Circle { center, radius } => {
+                self.in_world(&format!("{path}.circle.center"), center);
+                self.positive(&format!("{path}.circle.radius"), radius);
+            }
+        }
+    }
+}
+
+/// Whether the corners turn the same way everywhere, without any straight angle.
+fn is_convex(points: &[DVec2]) -> bool {
+    let turns: Vec<f64> = (0..points.len())
+        .map(|index| {
+            let a = points[index];
+            let b = points[(index + 1) % points.len()];
+            let c = points[(index + 2) % points.len()];
+            (b - a).perp_dot(c - b)
+        })
+        .collect();
+    turns.iter().all(|&turn| turn > 0.) || turns.iter().all(|&turn| turn < 0.)
+}
+
+/// Checks that the values of a map make sense, returns all problems found.
+pub fn validate(map: &Map) -> Vec<MapError> {
+    let mut validator = Validator { errors: vec![] };
+
+    if let Some(players) = map.metadata.recommended_players {
+        if players == 0 || players > constants::MAX_PLAYERS {
+            validator.error(
+                "$.metadata.recommended_players",
+                format!("must be between 1 and {}", constants::MAX_PLAYERS),
+            );
+        }
+    }
+
+    validator.field_law("$.field_law", map.field_law);
+
+    let ship = &map.ship_config;
+    validator.not_negative("$.ship_config.force", ship.force);
+    validator.positive("$.ship_config.radius", ship.radius);
+    validator.positive("$.ship_config.mass", ship.mass);
+    validator.finite("$.ship_config.field", ship.field);
+    if let ControlModel::Rotational {
+        torque,
+        max_spin,
+        reverse,
+    } = ship.controls
+    {
+        let path = "$.ship_config.controls.rotational";
+        validator.positive(&format!("{path}.torque"), torque);
+        validator.positive(&format!("{path}.max_spin"), max_spin);
+        if validator.finite(&format!("{path}.reverse"), reverse) && !(0. ..=1.).contains(&reverse) {
+            validator.error(&format!("{path}.reverse"), "must be between 0 and 1");
+        }
+    }
+
+    if let Some(mines) = ship.mines {
+        let path = "$.ship_config.mines";
+        if mines.max == 0 {
+            validator.error(&format!("{path}.max"), "must be at least 1");
+        }
+        validator.positive(&format!("{path}.radius"), mines.radius);
+        validator.positive(&format!("{path}.trigger"), mines.trigger);
+        validator.not_negative(&format!("{path}.arm_seconds"), mines.arm_seconds);
+    }
+    if let Some(anchors) = ship.anchors {
+        let path = "$.ship_config.anchors";
+        if anchors.max == 0 {
+            validator.error(&format!("{path}.max"), "must be at least 1");
+        }
+        validator.positive(&format!("{path}.radius"), anchors.radius);
+        if validator.finite(&format!("{path}.field"), anchors.field) && anchors.field == 0. {
+            validator.error(&format!("{path}.field"), "must not be zero");
+        }
+        validator.positive(&format!("{path}.seconds"), anchors.seconds);
+    }
+    if let Some(tether) = ship.tether {
+        let path = "$.ship_config.tether";
+        validator.positive(&format!("{path}.range"), tether.range);
+        validator.positive(&format!("{path}.stiffness"), tether.stiffness);
+        validator.not_negative(&format!("{path}.damping"), tether.damping);
+        validator.positive(&format!("{path}.break_force"), tether.break_force);
+    }
+    if let Some(fuel) = ship.fuel {
+        validator.positive("$.ship_config.fuel.capacity", fuel.capacity);
+        validator.not_negative("$.ship_config.fuel.burn_rate", fuel.burn_rate);
+        validator.not_negative("$.ship_config.fuel.regeneration", fuel.regeneration);
+        validator.not_negative(
+            "$.ship_config.fuel.planet_regeneration",
+            fuel.planet_regeneration,
+        );
+        validator.not_negative("$.ship_config.fuel.shield_cost", fuel.shield_cost);
+        validator.not_negative("$.ship_config.fuel.deploy_cost", fuel.deploy_cost);
+    }
+
+    let locations: Vec<DVec2> = map
+        .planet_states()
+        .into_iter()
+        .map(|(location, _)| location)
+        .collect();
+    for (index, planet) in map.planets.iter().enumerate() {
+        let path = format!("$.planets[{index}]");
+        validator.planet(&path, planet, locations[index]);
+        validator.orbit(&path, map, index);
+        validator.kind(&path, map, index);
+        if let Some(law) = planet.field_law {
+            validator.field_law(&format!("{path}.field_law"), law);
+        }
+        if let FieldLaw::ConstantRange { range } = planet.field_law.unwrap_or(map.field_law) {
+            if range > 0. && range <= planet.radius {
+                validator.error(
+                    &format!("{path}.radius"),
+                    format!("must be below the range {range} of the field law"),
+                );
+            }
+        }
+        if let Some(name) = &planet.name {
+            if map.find_planet(name, index).is_some() {
+                validator.error(
+                    &format!("{path}.name"),
+                    format!("'{name}' is used by another planet"),
+                );
+            }
+        }
+    }
+    // None for planets without a start location, otherwise their parent if any,
+    // which orbiting planets are checked against by their closest approach
+    let parents: Vec<Option<Option<usize>>> = map
+        .planets
+        .iter()
+        .enumerate()
+        .map(|(index, planet)| match (&planet.orbit, planet.location) {
+            (Some(orbit), _) => map.find_planet(&orbit.parent, index).map(Some),
+            (None, Some(_)) => Some(None),
+            (None, None) => None,
+        })
+        .collect();
+    for (index, planet) in map.planets.iter().enumerate() {
+        for (other_index, other) in map.planets.iter().enumerate().take(index) {
+            let (Some(parent), Some(_)) = (parents[index], parents[other_index]) else {
+                continue;
+            };
+            let distance = locations[index].distance(locations[other_index]);
+            if parent != Some(other_index) && distance < planet.radius + other.radius {
+                validator.error(
+                    &format!("$.planets[{index}]"),
+                    format!("overlaps $.planets[{other_index}]"),
+                );
+            }
+        }
+    }
+
+    let spawn = &map.spawn;
+    for (index, area) in spawn.areas.iter().enumerate() {
+        validator.spawn_area(&format!("$.spawn.areas[{index}]"), area);
+    }
+    validator.not_negative("$.spawn.margin", spawn.margin);
+    if let Some(max_field) = spawn.max_field {
+        validator.positive("$.spawn.max_field", max_field);
+    }
+    if spawn.attempts == 0 {
+        validator.error("$.spawn.attempts", "must be at least 1");
+    }
+
+    for (index, point) in map.spawn_points.iter().enumerate() {
+        let path = format!("$.spawn_points[{index}]");
+        validator.in_world(&format!("{path}.location"), point.location);
+        if point.name.is_empty() {
+            validator.error(&format!("{path}.name"), "must not be empty");
+        } else if map.spawn_points[..index]
+            .iter()
+            .any(|other| other.name == point.name)
+        {
+            validator.error(
+                &format!("{path}.name"),
+                format!("'{}' is used by another spawn point", point.name),
+            );
+        }
+    }
+
+    for (index, zone) in map.zones.iter().enumerate() {
+        validator.zone(&format!("$.zones[{index}]"), zone);
+    }
+    for (index, wall) in map.walls.iter().enumerate() {
+        validator.wall(&format!("$.walls[{index}]"), wall);
+    }
+    for (index, spawner) in map.pickups.iter().enumerate() {
+        validator.pickup(&format!("$.pickups[{index}]"), spawner);
+    }
+
+    validator.errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planet(x: f64, y: f64, radius: f64, mass: f64) -> serde_json::Value {
+        serde_json::json!({"location": [x, y], "radius": radius, "mass": mass, "field": 0.1})
+    }
+
+    #[test]
+    fn report_invalid_orbits() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "planets": [
+                {"name": "sun", "location": [0.5, 0.5], "radius": 0.05, "mass": 1000.0, "field": 0.1},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "moon", "semi_major_axis": 0.2}},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "sun", "semi_major_axis": 0.2, "eccentricity": 1.0}},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "sun", "semi_major_axis": 0.05}},
+                {"name": "moon", "radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "sun", "semi_major_axis": 0.7, "phase": 1.0}},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.planets[1].orbit.parent",
+                "$.planets[2].orbit.eccentricity",
+                "$.planets[3].orbit.semi_major_axis",
+                "$.planets[4].orbit",
+                "$.planets[5].location",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_invalid_paths() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "planets": [
+                {"radius": 0.01, "mass": 1.0, "field": 0.1, "motion": {"circle":
+                    {"center": [0.5, 0.5], "radius": 0.3, "period": 2.0}}},
+                {"radius": 0.01, "mass": 1.0, "field": 0.1, "motion": {"circle":
+                    {"center": [0.9, 0.5], "radius": 0.3, "period": 2.0}}},
+                {"radius": 0.01, "mass": 1.0, "field": 0.1, "motion": {"waypoints": {
+                    "waypoints": [
+                        {"time": 1.0, "location": [0.1, 0.1]},
+                        {"time": 1.0, "location": [0.2, 0.1]},
+                    ],
+                    "repeat": true
+                }}},
+                {"location": [0.9, 0.9], "radius": 0.01, "mass": 1.0, "field": 0.1,
+                    "motion": "anchored"},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.planets[1].motion.circle",
+                "$.planets[2].motion.waypoints.waypoints[1].time",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_invalid_kinds() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "planets": [
+                {"name": "hole", "location": [0.2, 0.2], "radius": 0.01, "mass": 1.0,
+                    "field": 0.1, "kind": {"black_hole": {"horizon": 0.0}}},
+                {"name": "push", "location": [0.8, 0.2], "radius": 0.01, "mass": 1.0,
+                    "field": -0.1, "kind": "repulsor"},
+                {"name": "in", "location": [0.2, 0.8], "radius": 0.01, "mass": 1.0,
+                    "field": 0.0, "kind": {"wormhole": {"exit": "hole"}}},
+                {"name": "out", "location": [0.8, 0.8], "radius": 0.01, "mass": 1.0,
+                    "field": 0.0, "kind": {"wormhole": {"exit": "out"}}},
+                {"location": [0.5, 0.8], "radius": 0.01, "mass": 1.0,
+                    "field": 0.0, "kind": {"wormhole": {"exit": "nowhere"}}},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "push", "semi_major_axis": 0.1}},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.planets[0].kind.black_hole.horizon",
+                "$.planets[1].field",
+                "$.planets[2].kind.wormhole.exit",
+                "$.planets[3].kind.wormhole.exit",
+                "$.planets[4].kind.wormhole.exit",
+                "$.planets[5].orbit.parent",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_invalid_zones() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "zones": [
+                {"shape": {"circle": {"center": [0.5, 0.5], "radius": 0.0}},
+                    "effects": [{"drag": {"linear": -1.0}}]},
+                {"shape": {"polygon": {"points": [[0.1, 0.1], [0.2, 0.1]]}},
+                    "effects": []},
+                {"name": "nebula", "shape": {"polygon": {"points": [[0.1, 0.1], [0.2, 0.1], [0.2, 0.2]]}},
+                    "effects": [{"current": {"force": [1.0, 0.0]}}, {"dampening": {"factor": -0.5}}, "blackout"]},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.zones[0].shape.circle.radius",
+                "$.zones[0].effects[0].drag.linear",
+                "$.zones[1].shape.polygon.points",
+                "$.zones[1].effects",
+                "$.zones[2].effects[1].dampening.factor",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_invalid_walls() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "walls": [
+                {"shape": {"segment": {"from": [0.1, 0.1], "to": [0.1, 0.1]}}},
+                {"shape": {"polygon": {"points": [[0.1, 0.1], [0.2, 0.1]]}}},
+                {"shape": {"polygon": {"points": [[0.1, 0.1], [0.3, 0.1], [0.2, 0.15], [0.2, 0.3]]}},
+                    "hit": "destroy"},
+                {"shape": {"polygon": {"points": [[0.5, 0.5], [0.5, 0.6], [0.6, 0.6], [0.6, 0.5]]}}},
+                {"shape": {"segment": {"from": [0.1, 0.9], "to": [0.9, 0.9]}}, "hit": "bounce"},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.walls[0].shape.segment",
+                "$.walls[1].shape.polygon.points",
+                "$.walls[2].shape.polygon.points",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_invalid_pickups() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "pickups": [
+                {"kind": {"speed_boost": {"factor": 0.0, "seconds": 2.0}}, "interval": 5.0},
+                {"kind": {"shield": {"seconds": 3.0}}, "interval": -1.0, "max": 0},
+                {"kind": {"energy": {"amount": 1.0}}, "interval": 5.0, "location": [1.5, 0.5]},
+                {"kind": {"mass": {"factor": 2.0, "seconds": 4.0}}, "interval": 5.0,
+                    "gravity": true, "radius": 0.01},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.pickups[0].kind.speed_boost.factor",
+                "$.pickups[1].interval",
+                "$.pickups[1].max",
+                "$.pickups[2].location",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_invalid_field_laws() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "field_law": {"constant_range": {"range": 0.15}},
+            "planets": [
+                {"name": "sun", "location": [0.5, 0.5], "radius": 0.05, "mass": 1000.0, "field": 0.1},
+                {"radius": 0.01, "mass": 1.0, "field": 0.0,
+                    "orbit": {"parent": "sun", "semi_major_axis": 0.2}},
+                {"location": [0.2, 0.2], "radius": 0.2, "mass": 1.0, "field": 0.1},
+                {"location": [0.8, 0.2], "radius": 0.01, "mass": 1.0, "field": 0.1,
+                    "field_law": {"yukawa": {"range": -1.0}}},
+                {"location": [0.8, 0.8], "radius": 0.01, "mass": 1.0, "field": 0.1,
+                    "field_law": {"plummer": {"softening": 0.01}}},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.planets[1].orbit.semi_major_axis",
+                "$.planets[2].radius",
+                "$.planets[3].field_law.yukawa.range",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_invalid_deployables() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "ship_config": {
+                "force": 1.0, "radius": 0.01, "mass": 1.0, "field": 1.0,
+                "mines": {"max": 0, "radius": 0.005, "trigger": 0.02},
+                "anchors": {"max": 2, "radius": 0.01, "field": 0.0, "seconds": 5.0},
+                "tether": {"range": 0.1, "stiffness": 5.0, "damping": -1.0, "break_force": 2.0},
+            }
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.ship_config.mines.max",
+                "$.ship_config.anchors.field",
+                "$.ship_config.tether.damping",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn report_nonsensical_values() {
+        let map: Map = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "ship_config": {"force": 1.0, "radius": 0.01, "mass": 0.0, "field": 1.0},
+            "planets": [
+                planet(0.5, 0.5, -0.1, 1.),
+                planet(1.5, 0.5, 0.1, -1.),
+                planet(0.2, 0.2, 0.1, 1.),
+                planet(0.25, 0.2, 0.1, 1.),
+            ],
+            "spawn_points": [
+                {"name": "a", "location": [0.9, 0.9]},
+                {"name": "a", "location": [0.8, 0.9]},
+            ]
+        }))
+        .unwrap();
+        let paths: Vec<String> = validate(&map).into_iter().map(|error| error.path).collect();
+        assert_eq!(
+            vec![
+                "$.ship_config.mass",
+                "$.planets[0].radius",
+                "$.planets[1].location",
+                "$.planets[1].mass",
+                "$.planets[3]",
+                "$.spawn_points[1].name",
+            ],
+            paths
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::object::Object;
+use super::zone;
+
+/// Free distance kept when pushing an object out of a wall
+const WALL_GAP: f64 = 1e-9;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum WallShape {
+    Segment {
+        #[schemars(with = "[f64; 2]")]
+        from: DVec2,
+        #[schemars(with = "[f64; 2]")]
+        to: DVec2,
+    },
+    /// Corners of a convex polygon in order around it
+    Polygon {
+        #[schemars(with = "Vec<[f64; 2]>")]
+        points: Vec<DVec2>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Hit {
+    /// Ships are pushed out and reflected
+    #[default]
+    Bounce,
+    /// Ships are destroyed
+    Destroy,
+}
+
+/// Static obstacle that ships collide with but that has no field.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Wall {
+    pub shape: WallShape,
+    #[serde(default)]
+    pub hit: Hit,
+}
+
+fn closest_on_segment(from: DVec2, to: DVec2, point: DVec2) -> DVec2 {
+    let along = to - from;
+    if along.length_squared() == 0. {
+        return from;
+    }
+    let t = ((point - from).dot(along) / along.length_squared()).clamp(0., 1.);
+    from + t * along
+}
+
+/// Point where the segments `a` and `b` cross, if they do.
+fn intersection(a: (DVec2, DVec2), b: (DVec2, DVec2)) -> Option<DVec2> {
+    let (r, s) = (a.1 - a.0, b.1 - b.0);
+    let denominator = r.perp_dot(s);
+    if denominator == 0. {
+        return None;
+    }
+    let t = (b.0 - a.0).perp_dot(s) / denominator;
+    let u = (b.0 - a.0).perp_dot(r) / denominator;
+    ((0. ..=1.).contains(&t) && (0. ..=1.).contains(&u)).then_some(a.0 + t * r)
+}
+
+impl Wall {
+    pub fn edges(&self) -> Vec<(DVec2, DVec2)> {
+        match &self.shape {
+            &WallShape::Segment { from, to } => vec![(from, to)],
+            WallShape::Polygon { points } => points
+                .iter()
+                .enumerate()
+                .map(|(index, &point)| (point, points[(index + 1) % points.len()]))
+                .collect(),
+        }
+    }
+
+    fn contains(&self, point: DVec2) -> bool {
+        match &self.shape {
+            WallShape::Segment { .. } => false,
+            WallShape::Polygon { points } => zone::polygon_contains(points, point),
+        }
+    }
+
+    fn closest(&self, point: DVec2) -> DVec2 {
+        self.edges()
+            .into_iter()
+            .map(|(from, to)| closest_on_segment(from, to, point))
+            .min_by(|a, b| a.distance(point).total_cmp(&b.distance(point)))
+            .unwrap_or(point)
+    }
+
+    /// Distance from `point` to the wall, 0 inside of it.
+    pub fn distance(&self, point: DVec2) -> f64 {
+        match self.contains(point) {
+            true => 0.,
+            false => self.closest(point).distance(point),
+        }
+    }
+
+    /// Point of contact and normal pointing away from the wall if `object`, which
+    /// moved from `previous`, touches or passed through the wall.
+    pub fn contact(&self, previous: DVec2, object: &Object) -> Option<(DVec2, DVec2)> {
+        let location = object.location;
+        // crossed an edge within one update, the first one on the way counts
+        let crossing = self
+            .edges()
+            .into_iter()
+            .filter_map(|edge| Some((intersection((previous, location), edge)?, edge)))
+            .filter(|&(point, _)| point != previous)
+            .min_by(|a, b| a.0.distance(previous).total_cmp(&b.0.distance(previous)));
+        if let Some((point, (from, to))) = crossing {
+            let normal = (to - from).perp().normalize_or_zero();
+            let side = (previous - point).dot(normal).signum();
+            return Some((point, side * normal));
+        }
+        let closest = self.closest(location);
+        if self.contains(location) {
+            return Some((closest, (closest - location).normalize_or_zero()));
+        }
+        let offset = location - closest;
+        (offset.length() < object.radius).then(|| (closest, offset.normalize_or_zero()))
+    }
+
+    /// Pushes `object` out of the wall and reflects its velocity.
+    pub fn bounce(object: &mut Object, contact: DVec2, normal: DVec2) {
+        object.location = contact + normal * (object.radius + WALL_GAP);
+        let into_wall = object.velocity.dot(normal).min(0.);
+        object.velocity -= 2. * into_wall * normal;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{
+        object::Update,
+        pickup::PickupKind,
+        space::{ShipConfig, Space},
+    };
+
+    fn space() -> Space {
+        Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            ..Default::default()
+        })
+    }
+
+    fn square(hit: Hit) -> Wall {
+        Wall {
+            shape: WallShape::Polygon {
+                points: vec![
+                    DVec2::new(0.4, 0.4),
+                    DVec2::new(0.6, 0.4),
+                    DVec2::new(0.6, 0.6),
+                    DVec2::new(0.4, 0.6),
+                ],
+            },
+            hit,
+        }
+    }
+
+    #[test]
+    fn touching_polygon_bounces_off_its_side() {
+        let wall = square(Hit::Bounce);
+        let mut ship = Object::new(DVec2::new(0.35, 0.5), 0.06, 1., 0., DVec2::new(1., 1.));
+        let (contact, normal) = wall.contact(DVec2::new(0.3, 0.5), &ship).unwrap();
+        assert_eq!(DVec2::new(0.4, 0.5), contact);
+        assert_eq!(DVec2::new(-1., 0.), normal);
+        Wall::bounce(&mut ship, contact, normal);
+        assert_eq!(DVec2::new(-1., 1.), ship.velocity);
+        assert!(wall.distance(ship.location) >= ship.radius);
+    }
+
+    #[test]
+    fn fast_object_does_not_pass_segment() {
+        let wall = Wall {
+            shape: WallShape::Segment {
+                from: DVec2::new(0.5, 0.),
+                to: DVec2::new(0.5, 1.),
+            },
+            hit: Hit::Bounce,
+        };
+        let ship = Object::new(DVec2::new(0.9, 0.5), 0.01, 1., 0., DVec2::new(10., 0.));
+        let (contact, normal) = wall.contact(DVec2::new(0.1, 0.5), &ship).unwrap();
+        assert_eq!(DVec2::new(0.5, 0.5), contact);
+        assert_eq!(DVec2::new(-1., 0.), normal);
+        let far = Object::new(DVec2::new(0.9, 0.5), 0.01, 1., 0., DVec2::ZERO);
+        assert!(wall.contact(DVec2::new(0.8, 0.5), &far).is_none());
+    }
+
+    #[test]
+    fn distance_to_walls() {
+        let wall = square(Hit::Destroy);
+        assert_eq!(0., wall.distance(DVec2::new(0.5, 0.5)));
+        assert!((wall.distance(DVec2::new(0.7, 0.5)) - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn walls_bounce_or_destroy_ships() {
+        let mut space = space();
+        let segment = |x| WallShape::Segment {
+            from: DVec2::new(x, 0.),
+            to: DVec2::new(x, 1.),
+        };
+        space.set_walls(vec![
+            Wall {
+                shape: segment(0.3),
+                hit: Hit::Destroy,
+            },
+            Wall {
+                shape: segment(0.7),
+                hit: Hit::Bounce,
+            },
+        ]);
+        space.add_ship(1, DVec2::new(0.55, 0.5));
+        space.add_ship(2, DVec2::new(0.45, 0.5));
+        space.ship_mut(1).unwrap().object_mut().velocity = DVec2::new(1., 0.);
+        space.ship_mut(2).unwrap().object_mut().velocity = DVec2::new(-1., 0.);
+        space.add_ship(3, DVec2::new(0.45, 0.2));
+        let shielded = space.ship_mut(3).unwrap();
+        shielded.object_mut().velocity = DVec2::new(-1., 0.);
+        shielded.collect(PickupKind::Shield { seconds: 1. });
+        space.update(0.1);
+        let ship = space.ship(1).unwrap();
+        assert!(ship.location.x < 0.7 - 0.1);
+        assert_eq!(DVec2::new(-1., 0.), ship.velocity);
+        assert_eq!(Some(0), space.deaths(1));
+        assert_eq!(Some(1), space.deaths(2));
+        // shielded ships bounce off deadly walls too
+        let ship = space.ship(3).unwrap();
+        assert!(ship.location.x > 0.3 + 0.1);
+        assert_eq!(DVec2::new(1., 0.), ship.velocity);
+        assert_eq!(Some(0), space.deaths(3));
+    }
+}
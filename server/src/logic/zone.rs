@@ -0,0 +1,271 @@
+use glam::DVec2;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::object::Object;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Shape {
+    Circle {
+        #[schemars(with = "[f64; 2]")]
+        center: DVec2,
+        radius: f64,
+    },
+    /// Corners in order around the area
+    Polygon {
+        #[schemars(with = "Vec<[f64; 2]>")]
+        points: Vec<DVec2>,
+    },
+}
+
+impl Shape {
+    pub fn contains(&self, point: DVec2) -> bool {
+        match self {
+            &Shape::Circle { center, radius } => center.distance(point) <= radius,
+            Shape::Polygon { points } => polygon_contains(points, point),
+        }
+    }
+}
+
+pub fn polygon_contains(points: &[DVec2], point: DVec2) -> bool {
+    // count crossings of a ray to the right of the point
+    let mut inside = false;
+    for (index, &a) in points.iter().enumerate() {
+        let b = points[(index + 1) % points.len()];
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Effect {
+    /// Force against the velocity `v` of `linear * v + quadratic * |v| * v`
+    Drag {
+        #[serde(default)]
+        linear: f64,
+        #[serde(default)]
+        quadratic: f64,
+    },
+    /// Constant force
+    Current {
+        #[schemars(with = "[f64; 2]")]
+        force: DVec2,
+    },
+    /// Multiplies the thrust of ships
+    Dampening { factor: f64 },
+    /// Hides ships inside from other players
+    Blackout,
+    /// Energy per second regained by ships with limited fuel
+    Refuel { rate: f64 },
+}
+
+/// Area of the map changing the motion or visibility of objects inside.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Zone {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub shape: Shape,
+    pub effects: Vec<Effect>,
+}
+
+impl Zone {
+    fn force(&self, object: &Object) -> DVec2 {
+        self.effects
+            .iter()
+            .map(|effect| match *effect {
+                Effect::Drag { linear, quadratic } => {
+                    let velocity = object.velocity;
+                    -(linear + quadratic * velocity.length()) * velocity
+                }
+                Effect::Current { force } => force,
+                Effect::Dampening { .. } | Effect::Blackout | Effect::Refuel { .. } => DVec2::ZERO,
+            })
+            .sum()
+    }
+}
+
+/// Sum of the forces of all zones containing `object`.
+pub fn force(zones: &[Zone], object: &Object) -> DVec2 {
+    zones
+        .iter()
+        .filter(|zone| zone.shape.contains(object.location))
+        .map(|zone| zone.force(object))
+        .sum()
+}
+
+/// Factor of the thrust at `location`, the product of all dampening there.
+pub fn thrust_factor(zones: &[Zone], location: DVec2) -> f64 {
+    zones
+        .iter()
+        .filter(|zone| zone.shape.contains(location))
+        .flat_map(|zone| zone.effects.iter())
+        .map(|effect| match *effect {
+            Effect::Dampening { factor } => factor,
+            _ => 1.,
+        })
+        .product()
+}
+
+/// Sum of the refuel rates at `location`.
+pub fn refuel_rate(zones: &[Zone], location: DVec2) -> f64 {
+    zones
+        .iter()
+        .filter(|zone| zone.shape.contains(location))
+        .flat_map(|zone| zone.effects.iter())
+        .map(|effect| match *effect {
+            Effect::Refuel { rate } => rate,
+            _ => 0.,
+        })
+        .sum()
+}
+
+pub fn is_hidden(zones: &[Zone], location: DVec2) -> bool {
+    zones.iter().any(|zone| {
+        zone.shape.contains(location)
+            && zone
+                .effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::Blackout))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{
+        deployable::{DeployKind, MineConfig},
+        object::Update,
+        space::{ShipConfig, Space},
+        tether::TetherConfig,
+    };
+
+    fn square() -> Shape {
+        Shape::Polygon {
+            points: vec![
+                DVec2::new(0.2, 0.2),
+                DVec2::new(0.4, 0.2),
+                DVec2::new(0.4, 0.4),
+                DVec2::new(0.2, 0.4),
+            ],
+        }
+    }
+
+    #[test]
+    fn shapes_contain_points() {
+        assert!(square().contains(DVec2::new(0.3, 0.3)));
+        assert!(!square().contains(DVec2::new(0.5, 0.3)));
+        assert!(!square().contains(DVec2::new(0.3, 0.1)));
+        let circle = Shape::Circle {
+            center: DVec2::new(0.5, 0.5),
+            radius: 0.1,
+        };
+        assert!(circle.contains(DVec2::new(0.55, 0.55)));
+        assert!(!circle.contains(DVec2::new(0.6, 0.6)));
+    }
+
+    #[test]
+    fn effects_add_up_inside_zones() {
+        let zones = vec![
+            Zone {
+                name: None,
+                shape: square(),
+                effects: vec![
+                    Effect::Drag {
+                        linear: 1.,
+                        quadratic: 2.,
+                    },
+                    Effect::Dampening { factor: 0.5 },
+                ],
+            },
+            Zone {
+                name: None,
+                shape: Shape::Circle {
+                    center: DVec2::new(0.4, 0.4),
+                    radius: 0.1,
+                },
+                effects: vec![
+                    Effect::Current {
+                        force: DVec2::new(0., 1.),
+                    },
+                    Effect::Dampening { factor: 0.5 },
+                    Effect::Blackout,
+                ],
+            },
+        ];
+        let object = Object::new(DVec2::new(0.35, 0.35), 0.01, 1., 0., DVec2::new(0.5, 0.));
+        assert_eq!(DVec2::new(-1., 1.), force(&zones, &object));
+        assert_eq!(0.25, thrust_factor(&zones, object.location));
+        assert!(is_hidden(&zones, object.location));
+        assert_eq!(1., thrust_factor(&zones, DVec2::new(0.8, 0.8)));
+        assert!(!is_hidden(&zones, DVec2::new(0.25, 0.25)));
+    }
+
+    #[test]
+    fn zones_slow_down_and_hide_ships() {
+        let mut space = Space::new(ShipConfig {
+            force: 0.1,
+            radius: 0.1,
+            mass: 2.,
+            field: 1.,
+            mines: Some(MineConfig {
+                max: 1,
+                radius: 0.01,
+                trigger: 0.01,
+                arm_seconds: 1.,
+            }),
+            tether: Some(TetherConfig {
+                range: 1.,
+                stiffness: 0.,
+                damping: 0.,
+                break_force: 1.,
+            }),
+            ..Default::default()
+        });
+        space.set_zones(vec![Zone {
+            name: None,
+            shape: Shape::Circle {
+                center: DVec2::new(0.2, 0.5),
+                radius: 0.2,
+            },
+            effects: vec![
+                Effect::Drag {
+                    linear: 1.,
+                    quadratic: 0.,
+                },
+                Effect::Dampening { factor: 0. },
+                Effect::Blackout,
+            ],
+        }]);
+        space.add_ship(1, DVec2::new(0.2, 0.5));
+        space.add_ship(2, DVec2::new(0.8, 0.5));
+        for id in 1..=2 {
+            space.ship_mut(id).unwrap().object_mut().velocity = DVec2::new(0.1, 0.);
+            space.move_ship(id, Some(0.));
+        }
+        space.update(0.1);
+        assert!(space.ship(1).unwrap().velocity.x < 0.1);
+        assert!(space.ship(2).unwrap().velocity.x > 0.1);
+
+        space.deploy(1, DeployKind::Mine);
+        space.latch_tether(2);
+
+        let visible = |viewer| {
+            let view: Space = bincode::deserialize(&space.get_state_binary_for(viewer)).unwrap();
+            (
+                view.ships().map(|(id, _)| id).collect::<Vec<u8>>(),
+                view.deployables().count(),
+                view.tethers().count(),
+            )
+        };
+        assert_eq!((vec![1, 2], 1, 1), visible(Some(1)));
+        assert_eq!((vec![2], 0, 0), visible(Some(2)));
+        assert_eq!((vec![2], 0, 0), visible(None));
+    }
+}
@@ -1,22 +1,216 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use server::{GeneratorConfig, Interval};
 
 /// Space Game Server
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Path to json file with map configuration
-    #[arg(short, long, default_value_t = String::from("maps/example.json"))]
-    path: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Json, toml or ron map files or directories of them, played in rotation and reloaded on changes
+    #[arg(short, long, num_args = 1.., default_value = "maps/example.json")]
+    path: Vec<String>,
+
+    /// Seconds of game time a match lasts before switching to the next map
+    #[arg(long, value_parser = positive_seconds)]
+    match_seconds: Option<f64>,
 
     /// Ip address to bind
     #[arg(short, long, default_value_t = String::from("0.0.0.0:8888"))]
     addr: String,
+
+    /// Seed for all randomness, overrides the map seed
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Directory to record replays to
+    #[arg(short, long)]
+    record: Option<String>,
+
+    /// Checkpoint file to continue a saved game from instead of the map
+    #[arg(long)]
+    resume: Option<String>,
+
+    /// Checkpoint file to periodically save the game to, json or bincode by extension
+    #[arg(long)]
+    checkpoint: Option<String>,
+
+    /// Seconds between checkpoints
+    #[arg(long, default_value_t = 60., value_parser = positive_seconds)]
+    checkpoint_seconds: f64,
+
+    /// Read admin commands from stdin
+    #[arg(long)]
+    admin: bool,
+
+    /// Unix socket to accept admin commands on
+    #[arg(long)]
+    admin_socket: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check a map file and list its problems
+    ValidateMap { path: String },
+    /// Print the JSON Schema of map files
+    MapSchema,
+    /// Translate a map between json, toml and ron, chosen by the file extensions
+    ConvertMap { input: String, output: String },
+    /// Generate a random map and write it as json, toml or ron, chosen by the file extension
+    GenerateMap {
+        output: String,
+
+        /// Seed of the map, random if unset
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[arg(long, default_value_t = GeneratorConfig::default().planets)]
+        planets: usize,
+
+        /// Planet masses as min..max
+        #[arg(long, default_value_t = GeneratorConfig::default().mass)]
+        mass: Interval,
+
+        /// Planet radii as min..max
+        #[arg(long, default_value_t = GeneratorConfig::default().radius)]
+        radius: Interval,
+
+        /// Planet fields as min..max
+        #[arg(long, default_value_t = GeneratorConfig::default().field)]
+        field: Interval,
+
+        /// Minimal free distance between bodies
+        #[arg(long, default_value_t = GeneratorConfig::default().separation, value_parser = distance)]
+        separation: f64,
+
+        /// Moons orbiting the planets
+        #[arg(long, default_value_t = GeneratorConfig::default().moons)]
+        moons: usize,
+
+        /// Asteroid belts orbiting the planets
+        #[arg(long, default_value_t = GeneratorConfig::default().belts)]
+        belts: usize,
+
+        #[arg(long, default_value_t = GeneratorConfig::default().belt_asteroids)]
+        belt_asteroids: usize,
+
+        /// Start the server on the generated map
+        #[arg(long)]
+        serve: bool,
+    },
+}
+
+fn positive_seconds(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(seconds) if seconds > 0. => Ok(seconds),
+        Ok(seconds) => Err(format!("must be positive, found {seconds}")),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn distance(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(distance) if distance.is_finite() && distance >= 0. => Ok(distance),
+        Ok(distance) => Err(format!("must be finite and not negative, found {distance}")),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 fn main() {
-    let args = Args::parse();
+    let mut args = Args::parse();
+
+    match args.command.take() {
+        Some(Command::ValidateMap { path }) => {
+            match server::validate_map(&path) {
+                Ok(()) => println!("{path} is valid"),
+                Err(e) => {
+                    eprintln!("{path} is invalid:\n{e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(Command::MapSchema) => {
+            println!("{}", server::map_schema());
+            return;
+        }
+        Some(Command::ConvertMap { input, output }) => {
+            if let Err(e) = server::convert_map(&input, &output) {
+                eprintln!("Cannot convert {input}:\n{e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::GenerateMap {
+            output,
+            seed,
+            planets,
+            mass,
+            radius,
+            field,
+            separation,
+            moons,
+            belts,
+            belt_asteroids,
+            serve,
+        }) => {
+            let seed = seed.unwrap_or_else(rand::random);
+            let generator = GeneratorConfig {
+                seed,
+                planets,
+                mass,
+                radius,
+                field,
+                separation,
+                moons,
+                belts,
+                belt_asteroids,
+                ..GeneratorConfig::default()
+            };
+            let result =
+                server::generate(&generator).and_then(|map| server::write_map(&map, &output));
+            if let Err(e) = result {
+                eprintln!("Cannot generate map: {e}");
+                std::process::exit(1);
+            }
+            println!("Generated {output} with seed {seed}");
+            if !serve {
+                return;
+            }
+            args.path = vec![output];
+        }
+        None => {}
+    }
 
-    if let Err(e) = server::run(&args.path, &args.addr) {
+    let config = server::Config {
+        paths: args.path,
+        match_seconds: args.match_seconds,
+        addr: args.addr,
+        seed: args.seed,
+        record: args.record,
+        resume: args.resume,
+        checkpoint: args.checkpoint,
+        checkpoint_seconds: args.checkpoint_seconds,
+        admin: args.admin,
+        admin_socket: args.admin_socket,
+    };
+    let result = server::start(&config).and_then(|server| {
+        match &config.resume {
+            Some(path) => println!("Resuming {path} at tick {}", server.tick),
+            None => {
+                if let Some(name) = &server.map_name {
+                    println!("Map: {name}");
+                }
+            }
+        }
+        println!("Seed: {}", server.seed);
+        if let Some(path) = &server.replay {
+            println!("Recording replay to {}", path.display());
+        }
+        server.serve()
+    });
+    if let Err(e) = result {
         eprintln!("Server error: {e}");
         std::process::exit(1);
     }
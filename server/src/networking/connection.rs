@@ -1,86 +1,309 @@
 use bus::{Bus, BusReader};
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io::{BufReader, Read, Write};
-use std::net::TcpListener;
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
+use std::time::Duration;
 
 use crate::constants;
+use crate::logic::space::Space;
+use crate::ui::command::Command;
+
+use super::protocol;
 
 struct Client<T: Write + Read> {
     stream: T,
-    command_sender: Sender<Vec<u8>>,
-    state_receiver: BusReader<Vec<u8>>,
+    addr: Option<SocketAddr>,
+    command_sender: Sender<Command>,
+    state_receiver: BusReader<Arc<Space>>,
+    players: Arc<Mutex<Players>>,
+}
+
+pub struct Player {
+    pub addr: Option<SocketAddr>,
+    kicked: bool,
+}
+
+/// Player slots, the ship id of a slot is its index + 1.
+pub struct Players {
+    slots: Vec<Option<Player>>,
+    banned: HashSet<IpAddr>,
+}
+
+impl Players {
+    pub fn new(max_players: u8) -> Players {
+        Players {
+            slots: (0..max_players).map(|_| None).collect(),
+            banned: HashSet::new(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &Player)> {
+        self.slots
+            .iter()
+            .zip(1..)
+            .filter_map(|(player, id)| player.as_ref().map(|player| (id, player)))
+    }
+
+    pub fn is_banned(&self, addr: Option<SocketAddr>) -> bool {
+        addr.is_some_and(|addr| self.banned.contains(&addr.ip()))
+    }
+
+    /// Disconnects the player, returns false if there is no such player.
+    pub fn kick(&mut self, id: u8) -> bool {
+        match self.slot_mut(id).and_then(Option::as_mut) {
+            Some(player) => {
+                player.kicked = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Disconnects the player and refuses new connections from its address.
+    pub fn ban(&mut self, id: u8) -> Option<IpAddr> {
+        let ip = self.slot(id)?.addr?.ip();
+        self.banned.insert(ip);
+        for player in self.slots.iter_mut().flatten() {
+            if player.addr.is_some_and(|addr| addr.ip() == ip) {
+                player.kicked = true;
+            }
+        }
+        Some(ip)
+    }
+
+    pub fn unban(&mut self, ip: IpAddr) -> bool {
+        self.banned.remove(&ip)
+    }
+
+    fn take(&mut self, addr: Option<SocketAddr>) -> Option<u8> {
+        let index = self.slots.iter().position(Option::is_none)?;
+        self.slots[index] = Some(Player {
+            addr,
+            kicked: false,
+        });
+        (index + 1).try_into().ok()
+    }
+
+    fn free(&mut self, id: u8) {
+        if let Some(slot) = self.slot_mut(id) {
+            *slot = None;
+        }
+    }
+
+    fn is_kicked(&self, id: u8) -> bool {
+        self.slot(id).is_some_and(|player| player.kicked)
+    }
+
+    fn slot(&self, id: u8) -> Option<&Player> {
+        let index = usize::from(id).checked_sub(1)?;
+        self.slots.get(index)?.as_ref()
+    }
+
+    fn slot_mut(&mut self, id: u8) -> Option<&mut Option<Player>> {
+        let index = usize::from(id).checked_sub(1)?;
+        self.slots.get_mut(index)
+    }
 }
 
 pub fn handle_listen(
     addr: &str,
-    command_sender: Sender<Vec<u8>>,
-    state_bus: &Arc<Mutex<Bus<Vec<u8>>>>,
+    command_sender: Sender<Command>,
+    state_bus: &Arc<Mutex<Bus<Arc<Space>>>>,
+    players: &Arc<Mutex<Players>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let listener = TcpListener::bind(addr)?;
+    let max_connections =
+        usize::from(constants::MAX_PLAYERS) + usize::from(constants::MAX_SPECTATORS);
 
-    let mut connections: HashMap<usize, JoinHandle<()>> = HashMap::new();
-    for stream in listener.incoming().flatten() {
-        if let Some(id) = init_connection(&mut connections, constants::MAX_PLAYERS) {
-            // thread for every connection
+    let mut connections: Vec<JoinHandle<()>> = Vec::new();
+    for mut stream in listener.incoming().flatten() {
+        connections.retain(|handle| !handle.is_finished());
+        let peer_addr = stream.peer_addr().ok();
+        if connections.len() >= max_connections || players.lock().unwrap().is_banned(peer_addr) {
+            continue;
+        }
+        // thread for every connection
+        let command_sender = command_sender.clone();
+        let state_bus = Arc::clone(state_bus);
+        let players = Arc::clone(players);
+        connections.push(thread::spawn(move || {
+            let handshake = Duration::from_secs_f64(constants::HANDSHAKE_SECONDS);
+            let Some(role) = read_role(&mut stream, handshake) else {
+                return;
+            };
+            // only connections that completed the handshake hold up the state broadcast
             let client = Client {
                 stream,
-                command_sender: command_sender.clone(),
+                addr: peer_addr,
+                command_sender,
                 state_receiver: state_bus.lock().unwrap().add_rx(),
+                players,
             };
-            let handle = thread::spawn(move || {
-                handle_connection(id, client);
-            });
-            connections.insert(id.into(), handle);
-        }
+            handle_connection(client, role);
+        }));
     }
 
-    for (_, connection) in connections {
+    for connection in connections {
         connection.join().unwrap();
     }
 
     Ok(())
 }
 
-fn init_connection(
-    connections: &mut HashMap<usize, JoinHandle<()>>,
-    max_players: u8,
-) -> Option<u8> {
-    connections.retain(|_, handle| !handle.is_finished());
-    if connections.len() >= max_players.into() {
-        return None;
-    }
-    (1..max_players + 1).find(|&i| !connections.contains_key(&i.into()))
+/// Reads the requested role, `None` if the client sends none within `timeout` or an
+/// unknown one.
+fn read_role(stream: &mut TcpStream, timeout: Duration) -> Option<u8> {
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    let mut role = [0u8; 1];
+    stream.read_exact(&mut role).ok()?;
+    stream.set_read_timeout(None).ok()?;
+    [protocol::JOIN_PLAY, protocol::JOIN_SPECTATE]
+        .contains(&role[0])
+        .then_some(role[0])
 }
 
-fn handle_connection<T: Write + Read>(id: u8, mut client: Client<T>) {
-    // add ship
-    client.command_sender.send(vec![0, id]).unwrap();
+fn handle_connection<T: Write + Read>(mut client: Client<T>, role: u8) {
+    let mut id = None;
+    if role == protocol::JOIN_PLAY {
+        id = join(&client);
+    }
     loop {
         // listen last game state
-        let mut state_msg = client.state_receiver.recv().unwrap();
-        while let Ok(msg) = client.state_receiver.try_recv() {
-            state_msg = msg;
+        let mut space = client.state_receiver.recv().unwrap();
+        while let Ok(newer) = client.state_receiver.try_recv() {
+            space = newer;
         }
-        // send state to client
-        let mut msg = u32::to_be_bytes(state_msg.len().try_into().unwrap()).to_vec();
+        let mut state_msg = space.get_state_binary_for(id);
+        // send state with ship id to client
+        let mut msg = u32::to_be_bytes((state_msg.len() + 1).try_into().unwrap()).to_vec();
+        msg.push(id.unwrap_or(protocol::NO_SHIP));
         msg.append(&mut state_msg);
-        client.stream.write_all(&msg).unwrap();
+        if client.stream.write_all(&msg).is_err() {
+            break;
+        }
         // read command from client
         let mut buf_reader = BufReader::new(&mut client.stream);
-        let mut command_buf = [0u8; 9];
+        let mut command_buf = [0u8; protocol::COMMAND_SIZE];
         if buf_reader.read_exact(&mut command_buf).is_err() {
             break;
         }
 
+        // switch between playing and spectating
+        let flags = command_buf[0];
+        if id.is_some_and(|id| client.players.lock().unwrap().is_kicked(id)) {
+            break;
+        }
+        if flags & protocol::SPECTATE != 0 {
+            if let Some(id) = id.take() {
+                leave(&client, id);
+            }
+        } else if flags & protocol::PLAY != 0 && id.is_none() {
+            id = join(&client);
+        }
+
         // send command to game logic
-        let mut move_msg = vec![2, id];
-        move_msg.append(&mut command_buf.to_vec());
-        client.command_sender.send(move_msg).unwrap();
+        if let Some(id) = id {
+            let mut move_msg = vec![2, id];
+            move_msg.append(&mut command_buf.to_vec());
+            client
+                .command_sender
+                .send(Command::Player(move_msg))
+                .unwrap();
+        }
+    }
+    if let Some(id) = id {
+        leave(&client, id);
+    }
+}
+
+fn join<T: Write + Read>(client: &Client<T>) -> Option<u8> {
+    let id = client.players.lock().unwrap().take(client.addr)?;
+    // add ship
+    client
+        .command_sender
+        .send(Command::Player(vec![0, id]))
+        .unwrap();
+    Some(id)
+}
+
+fn leave<T: Write + Read>(client: &Client<T>, id: u8) {
+    // remove ship
+    client
+        .command_sender
+        .send(Command::Player(vec![1, id]))
+        .unwrap();
+    client.players.lock().unwrap().free(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(ip: [u8; 4]) -> Option<SocketAddr> {
+        Some(SocketAddr::from((ip, 1234)))
+    }
+
+    #[test]
+    fn read_role_within_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let timeout = Duration::from_millis(50);
+        let _silent = TcpStream::connect(addr).unwrap();
+        let mut accepted = listener.accept().unwrap().0;
+        assert_eq!(None, read_role(&mut accepted, timeout));
+        for (role, expected) in [
+            (protocol::JOIN_SPECTATE, Some(protocol::JOIN_SPECTATE)),
+            (7, None),
+        ] {
+            let mut client = TcpStream::connect(addr).unwrap();
+            client.write_all(&[role]).unwrap();
+            let mut accepted = listener.accept().unwrap().0;
+            assert_eq!(expected, read_role(&mut accepted, timeout));
+        }
+    }
+
+    #[test]
+    fn take_slots_until_full() {
+        let mut players = Players::new(2);
+        assert_eq!(Some(1), players.take(None));
+        assert_eq!(Some(2), players.take(None));
+        assert_eq!(None, players.take(None));
+    }
+
+    #[test]
+    fn take_freed_slot() {
+        let mut players = Players::new(2);
+        players.take(None);
+        players.take(None);
+        players.free(1);
+        assert_eq!(Some(1), players.take(None));
+        assert_eq!(2, players.iter().count());
+    }
+
+    #[test]
+    fn kick_player() {
+        let mut players = Players::new(2);
+        players.take(None);
+        assert!(players.kick(1));
+        assert!(players.is_kicked(1));
+        assert!(!players.kick(2));
+    }
+
+    #[test]
+    fn ban_kicks_all_players_from_address() {
+        let mut players = Players::new(3);
+        players.take(addr([10, 0, 0, 1]));
+        players.take(addr([10, 0, 0, 2]));
+        players.take(addr([10, 0, 0, 1]));
+        assert!(players.ban(1).is_some());
+        assert!(players.is_kicked(3));
+        assert!(!players.is_kicked(2));
+        assert!(players.is_banned(addr([10, 0, 0, 1])));
+        assert!(players.unban(addr([10, 0, 0, 1]).unwrap().ip()));
+        assert!(!players.is_banned(addr([10, 0, 0, 1])));
     }
-    //remove ship
-    client.command_sender.send(vec![1, id]).unwrap();
 }
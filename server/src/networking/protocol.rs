@@ -0,0 +1,40 @@
+//! Wire format shared by the server and clients.
+//!
+//! On connect the client sends one role byte (`JOIN_PLAY` or `JOIN_SPECTATE`).
+//! Every state frame is a big endian `u32` length followed by the receiver's
+//! ship id (`NO_SHIP` when spectating) and the bincode encoded `Space`, without
+//! the ships of others hidden in blackout zones.
+//! Every command is `COMMAND_SIZE` bytes: a flags byte, a big endian `f64` direction
+//! and an actions byte.
+
+pub const JOIN_PLAY: u8 = 0;
+pub const JOIN_SPECTATE: u8 = 1;
+
+pub const NO_SHIP: u8 = 0;
+
+/// Largest state frame length clients accept, so a bad header cannot exhaust their memory
+pub const MAX_STATE_SIZE: usize = 1 << 24;
+
+pub const COMMAND_SIZE: usize = 10;
+
+/// Thrust in the sent direction, or forward under rotational control
+pub const MOVE: u8 = 1;
+/// Give up the ship and spectate
+pub const SPECTATE: u8 = 1 << 1;
+/// Take a ship if a player slot is free
+pub const PLAY: u8 = 1 << 2;
+/// Turn towards decreasing angles under rotational control
+pub const TURN_LEFT: u8 = 1 << 3;
+/// Turn towards increasing angles under rotational control
+pub const TURN_RIGHT: u8 = 1 << 4;
+/// Thrust in reverse under rotational control
+pub const REVERSE: u8 = 1 << 5;
+/// Drop a mine where the ship is
+pub const MINE: u8 = 1 << 6;
+/// Drop a gravity anchor where the ship is
+pub const ANCHOR: u8 = 1 << 7;
+
+/// Latch onto the closest planet or ship in range unless tethered already, in the actions byte
+pub const TETHER_LATCH: u8 = 1;
+/// Release the tether, in the actions byte
+pub const TETHER_RELEASE: u8 = 1 << 1;
@@ -0,0 +1,424 @@
+use glam::DVec2;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use crate::logic::map::{self, MapRotation};
+use crate::logic::space::Space;
+use crate::logic::update::Clock;
+use crate::networking::connection::Players;
+
+use super::command::Command;
+
+const HELP: &str = "\
+players                                  list connected players
+kick <id>                                disconnect a player
+ban <id>                                 disconnect a player and refuse its address
+unban <ip>                               accept connections from an address again
+pause | resume                           stop or continue the simulation
+tick <update|state> <seconds>            change real time between updates or state broadcasts
+planets                                  list planets
+planet add <x> <y> <radius> <mass> <field> [<vx> <vy>]
+planet delete <index>
+map <path>                               switch to another map keeping the players
+say [<message>]                          show a message to all players, clear it if empty";
+
+/// Admin commands applied by the game loop.
+#[derive(Debug)]
+pub enum AdminCommand {
+    Pause,
+    Resume,
+    UpdateTick(f64),
+    StateTick(f64),
+    AddPlanet {
+        location: DVec2,
+        radius: f64,
+        mass: f64,
+        field: f64,
+        velocity: DVec2,
+    },
+    DeletePlanet(usize),
+    LoadMap(Box<Space>),
+    Say(Option<String>),
+}
+
+#[derive(Debug)]
+enum ConsoleCommand {
+    Help,
+    Players,
+    Planets,
+    Kick(u8),
+    Ban(u8),
+    Unban(IpAddr),
+    LoadMap(PathBuf, Box<Space>),
+    Game(AdminCommand),
+}
+
+/// Applies `command` to the game, returns whether the space was changed.
+pub fn run_admin_command(space: &mut Space, clock: &mut Clock, command: AdminCommand) -> bool {
+    match command {
+        AdminCommand::Pause => clock.paused = true,
+        AdminCommand::Resume => clock.paused = false,
+        AdminCommand::UpdateTick(seconds) => clock.update_seconds = seconds,
+        AdminCommand::StateTick(seconds) => clock.state_seconds = seconds,
+        AdminCommand::AddPlanet {
+            location,
+            radius,
+            mass,
+            field,
+            velocity,
+        } => {
+            space.add_planet(location, mass, field, radius, velocity);
+            return true;
+        }
+        AdminCommand::DeletePlanet(index) => return space.remove_planet(index),
+        AdminCommand::LoadMap(map) => {
+            space.replace_map(*map);
+            return true;
+        }
+        AdminCommand::Say(message) => {
+            space.set_message(message);
+            return true;
+        }
+    }
+    false
+}
+
+fn parse<T: std::str::FromStr>(arg: Option<&str>, name: &str) -> Result<T, String> {
+    let arg = arg.ok_or(format!("missing {name}"))?;
+    arg.parse().map_err(|_| format!("invalid {name} '{arg}'"))
+}
+
+fn parse_number(arg: Option<&str>, name: &str) -> Result<f64, String> {
+    let value: f64 = parse(arg, name)?;
+    if !value.is_finite() {
+        return Err(format!("{name} must be finite"));
+    }
+    Ok(value)
+}
+
+fn parse_line(line: &str) -> Result<ConsoleCommand, String> {
+    let mut args = line.split_whitespace();
+    let command = match args.next() {
+        None | Some("help") => ConsoleCommand::Help,
+        Some("players") => ConsoleCommand::Players,
+        Some("planets") => ConsoleCommand::Planets,
+        Some("kick") => ConsoleCommand::Kick(parse(args.next(), "id")?),
+        Some("ban") => ConsoleCommand::Ban(parse(args.next(), "id")?),
+        Some("unban") => ConsoleCommand::Unban(parse(args.next(), "ip")?),
+        Some("pause") => ConsoleCommand::Game(AdminCommand::Pause),
+        Some("resume") => ConsoleCommand::Game(AdminCommand::Resume),
+        Some("tick") => {
+            let kind = args.next();
+            let seconds = parse_number(args.next(), "seconds")?;
+            if seconds <= 0. {
+                return Err(String::from("seconds must be positive"));
+            }
+            match kind {
+                Some("update") => ConsoleCommand::Game(AdminCommand::UpdateTick(seconds)),
+                Some("state") => ConsoleCommand::Game(AdminCommand::StateTick(seconds)),
+                _ => return Err(String::from("tick kind must be 'update' or 'state'")),
+            }
+        }
+        Some("planet") => match args.next() {
+            Some("add") => {
+                let location = DVec2::new(
+                    parse_number(args.next(), "x")?,
+                    parse_number(args.next(), "y")?,
+                );
+                if !(0. ..=1.).contains(&location.x) || !(0. ..=1.).contains(&location.y) {
+                    return Err(String::from("location must be within the map"));
+                }
+                let radius = parse_number(args.next(), "radius")?;
+                let mass = parse_number(args.next(), "mass")?;
+                let field = parse_number(args.next(), "field")?;
+                if radius <= 0. || mass < 0. {
+                    return Err(String::from(
+                        "radius must be positive and mass not negative",
+                    ));
+                }
+                let velocity = match args.next() {
+                    Some(vx) => DVec2::new(
+                        parse_number(Some(vx), "vx")?,
+                        parse_number(args.next(), "vy")?,
+                    ),
+                    None => DVec2::ZERO,
+                };
+                ConsoleCommand::Game(AdminCommand::AddPlanet {
+                    location,
+                    radius,
+                    mass,
+                    field,
+                    velocity,
+                })
+            }
+            Some("delete") => {
+                ConsoleCommand::Game(AdminCommand::DeletePlanet(parse(args.next(), "index")?))
+            }
+            _ => return Err(String::from("planet action must be 'add' or 'delete'")),
+        },
+        Some("map") => {
+            let path: String = parse(args.next(), "path")?;
+            let map =
+                map::load(Path::new(&path)).map_err(|e| format!("invalid map {path}: {e}"))?;
+            ConsoleCommand::LoadMap(PathBuf::from(path), Box::new(map))
+        }
+        Some("say") => {
+            let message = args.by_ref().collect::<Vec<_>>().join(" ");
+            ConsoleCommand::Game(AdminCommand::Say((!message.is_empty()).then_some(message)))
+        }
+        Some(command) => return Err(format!("unknown command '{command}', try 'help'")),
+    };
+    if args.next().is_some() {
+        return Err(String::from("too many arguments"));
+    }
+    Ok(command)
+}
+
+/// Handles admin console lines from stdin or the admin socket.
+#[derive(Clone)]
+pub struct Admin {
+    pub command_sender: Sender<Command>,
+    pub players: Arc<Mutex<Players>>,
+    pub space_counter: Arc<Mutex<Space>>,
+    /// Told about loaded maps so it reloads and rotates from them
+    pub rotation: Arc<Mutex<MapRotation>>,
+}
+
+impl Admin {
+    /// Runs a console line, returns the text to show the admin.
+    pub fn execute(&self, line: &str) -> String {
+        let command = match parse_line(line) {
+            Ok(command) => command,
+            Err(e) => return format!("error: {e}"),
+        };
+        match command {
+            ConsoleCommand::Help => String::from(HELP),
+            ConsoleCommand::Players => self.list_players(),
+            ConsoleCommand::Planets => {
+                let space = self.space_counter.lock().unwrap();
+                let mut output = String::new();
+                for (index, planet) in space.planets().enumerate() {
+                    writeln!(
+                        output,
+                        "{index}: location {} radius {} mass {} field {}",
+                        planet.location, planet.radius, planet.mass, planet.field
+                    )
+                    .unwrap();
+                }
+                output
+            }
+            ConsoleCommand::Kick(id) => match self.players.lock().unwrap().kick(id) {
+                true => format!("kicked player {id}"),
+                false => format!("error: no player {id}"),
+            },
+            ConsoleCommand::Ban(id) => match self.players.lock().unwrap().ban(id) {
+                Some(ip) => format!("banned {ip}"),
+                None => format!("error: no player {id} with a known address"),
+            },
+            ConsoleCommand::Unban(ip) => match self.players.lock().unwrap().unban(ip) {
+                true => format!("unbanned {ip}"),
+                false => format!("error: {ip} is not banned"),
+            },
+            ConsoleCommand::LoadMap(path, map) => {
+                // hold the rotation so the map watcher cannot load another map in between
+                let mut rotation = self.rotation.lock().unwrap();
+                let response = self.send(AdminCommand::LoadMap(map));
+                let tick = self.space_counter.lock().unwrap().get_tick();
+                rotation.play(&path, tick);
+                response
+            }
+            ConsoleCommand::Game(command) => self.send(command),
+        }
+    }
+
+    fn send(&self, command: AdminCommand) -> String {
+        match self.command_sender.send(Command::Admin(command)) {
+            Ok(()) => String::from("ok"),
+            Err(_) => String::from("error: game is not running"),
+        }
+    }
+
+    fn list_players(&self) -> String {
+        let players = self.players.lock().unwrap();
+        let space = self.space_counter.lock().unwrap();
+        let mut output = String::new();
+        for (id, player) in players.iter() {
+            let addr = player
+                .addr
+                .map_or(String::from("unknown"), |addr| addr.to_string());
+            let deaths = space.deaths(id).unwrap_or_default();
+            writeln!(output, "{id}: {addr} deaths {deaths}").unwrap();
+        }
+        output
+    }
+
+    pub fn run_console<R: BufRead, W: Write>(&self, input: R, mut output: W) {
+        for line in input.lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            let response = self.execute(&line);
+            if writeln!(output, "{}", response.trim_end()).is_err() {
+                return;
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn run_socket(&self, path: &str) -> std::io::Result<()> {
+        let listener = bind_socket(path)?;
+        for stream in listener.incoming().flatten() {
+            let admin = self.clone();
+            std::thread::spawn(move || {
+                if let Ok(input) = stream.try_clone() {
+                    admin.run_console(BufReader::new(input), stream);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Binds the admin socket, replacing the socket left by a previous run but no other
+/// file, and lets only the user running the server connect to it.
+#[cfg(unix)]
+fn bind_socket(path: &str) -> std::io::Result<std::os::unix::net::UnixListener> {
+    use std::fs::{self, Permissions};
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixListener;
+
+    if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    fs::set_permissions(path, Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::space::ShipConfig;
+    use std::sync::mpsc;
+
+    fn admin() -> (Admin, mpsc::Receiver<Command>) {
+        let (command_sender, command_receiver) = mpsc::channel();
+        let admin = Admin {
+            command_sender,
+            players: Arc::new(Mutex::new(Players::new(2))),
+            space_counter: Arc::new(Mutex::new(Space::new(ShipConfig::default()))),
+            rotation: Arc::new(Mutex::new(MapRotation::new(vec![PathBuf::from(
+                "../maps/example.json",
+            )]))),
+        };
+        (admin, command_receiver)
+    }
+
+    #[test]
+    fn parse_planet_add() {
+        let command = parse_line("planet add 0.5 0.5 0.1 10 0.1 0 0.2").unwrap();
+        match command {
+            ConsoleCommand::Game(AdminCommand::AddPlanet {
+                location, velocity, ..
+            }) => {
+                assert_eq!(DVec2::new(0.5, 0.5), location);
+                assert_eq!(DVec2::new(0., 0.2), velocity);
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn reject_invalid_commands() {
+        assert!(parse_line("planet add 2 0.5 0.1 10 0.1").is_err());
+        assert!(parse_line("planet add 0.5 0.5 -1 10 0.1").is_err());
+        assert!(parse_line("planet add 0.5 0.5 0.1 NaN 0.1").is_err());
+        assert!(parse_line("tick update 0").is_err());
+        assert!(parse_line("kick abc").is_err());
+        assert!(parse_line("pause now").is_err());
+        assert!(parse_line("map non_existing_map.json").is_err());
+        assert!(parse_line("launch").is_err());
+    }
+
+    #[test]
+    fn game_commands_go_through_queue() {
+        let (admin, command_receiver) = admin();
+        assert_eq!("ok", admin.execute("say hello  there"));
+        match command_receiver.try_recv() {
+            Ok(Command::Admin(AdminCommand::Say(Some(message)))) => {
+                assert_eq!("hello there", message)
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn loaded_maps_are_watched() {
+        let (admin, command_receiver) = admin();
+        admin.rotation.lock().unwrap().resume(0);
+        assert_eq!("ok", admin.execute("map ../maps/example.json"));
+        assert!(matches!(
+            command_receiver.try_recv(),
+            Ok(Command::Admin(AdminCommand::LoadMap(_)))
+        ));
+        assert_eq!(
+            Some(Path::new("../maps/example.json")),
+            admin.rotation.lock().unwrap().playing()
+        );
+    }
+
+    #[test]
+    fn run_admin_commands_in_game() {
+        let mut space = Space::new(ShipConfig::default());
+        let mut clock = Clock::default();
+        run_admin_command(&mut space, &mut clock, AdminCommand::Pause);
+        assert!(clock.paused);
+        run_admin_command(&mut space, &mut clock, AdminCommand::UpdateTick(0.5));
+        assert_eq!(0.5, clock.update_seconds);
+        assert!(!run_admin_command(
+            &mut space,
+            &mut clock,
+            AdminCommand::DeletePlanet(0)
+        ));
+        let command = AdminCommand::AddPlanet {
+            location: DVec2::new(0.5, 0.5),
+            radius: 0.1,
+            mass: 10.,
+            field: 0.1,
+            velocity: DVec2::ZERO,
+        };
+        assert!(run_admin_command(&mut space, &mut clock, command));
+        assert_eq!(1, space.planets().count());
+    }
+
+    #[test]
+    fn kick_unknown_player() {
+        let (admin, _) = admin();
+        assert_eq!("error: no player 1", admin.execute("kick 1"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_replaces_only_sockets() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join("space_game_admin_socket_test");
+        let path = path.to_str().unwrap();
+        std::fs::remove_file(path).ok();
+        std::fs::write(path, "keep").unwrap();
+        assert!(bind_socket(path).is_err());
+        assert_eq!("keep", std::fs::read_to_string(path).unwrap());
+
+        std::fs::remove_file(path).unwrap();
+        drop(bind_socket(path).unwrap());
+        let listener = bind_socket(path).unwrap();
+        let mode = std::fs::metadata(path).unwrap().permissions().mode();
+        assert_eq!(0o600, mode & 0o777);
+        drop(listener);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,17 +1,57 @@
+use crate::logic::control::Steering;
+use crate::logic::deployable::DeployKind;
 use crate::logic::space;
+use crate::networking::protocol;
 
-pub fn run_command(space: &mut space::Space, command: Vec<u8>) {
+use super::admin::AdminCommand;
+
+pub enum Command {
+    /// Byte encoded command of a connection, recorded in replays
+    Player(Vec<u8>),
+    Admin(AdminCommand),
+}
+
+pub fn run_command(space: &mut space::Space, command: &[u8]) {
     match command[0] {
-        0 => space.add_ship(command[1], space::gen_new_location()),
+        0 => {
+            let location = space.gen_new_location();
+            space.add_ship(command[1], location)
+        }
         1 => space.remove_ship(command[1]),
-        2 => space.move_ship(
-            command[1],
-            if command[2] == 0 {
-                None
-            } else {
-                Some(f64::from_be_bytes(command[3..].try_into().unwrap()))
-            },
-        ),
+        2 => {
+            space.move_ship(
+                command[1],
+                if command[2] & protocol::MOVE == 0 {
+                    None
+                } else {
+                    Some(f64::from_be_bytes(command[3..11].try_into().unwrap()))
+                },
+            );
+            space.steer_ship(command[1], steering(command[2]));
+            if command[2] & protocol::MINE != 0 {
+                space.deploy(command[1], DeployKind::Mine);
+            }
+            if command[2] & protocol::ANCHOR != 0 {
+                space.deploy(command[1], DeployKind::Anchor);
+            }
+            if command[11] & protocol::TETHER_RELEASE != 0 {
+                space.release_tether(command[1]);
+            }
+            if command[11] & protocol::TETHER_LATCH != 0 {
+                space.latch_tether(command[1]);
+            }
+        }
         _ => space.move_ship(1, None),
     }
 }
+
+/// Steering of ships under rotational control from command flags.
+fn steering(flags: u8) -> Steering {
+    let axis = |positive: u8, negative: u8| {
+        f64::from(u8::from(flags & positive != 0)) - f64::from(u8::from(flags & negative != 0))
+    };
+    Steering {
+        thrust: axis(protocol::MOVE, protocol::REVERSE),
+        turn: axis(protocol::TURN_RIGHT, protocol::TURN_LEFT),
+    }
+}
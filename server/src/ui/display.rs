@@ -1,6 +1,16 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DisplayType {
     Planet,
+    BlackHole,
+    Repulsor,
+    Wormhole,
     Ship,
+    Wall,
+    DeadlyWall,
+    Pickup,
+    Mine,
+    Anchor,
+    Tether,
 }
 
 pub struct DisplayInfo {
@@ -9,8 +19,18 @@ pub struct DisplayInfo {
     pub x: f64,
     pub y: f64,
     pub radius: f64,
+    /// Angle the object faces, if it has a front
+    pub heading: Option<f64>,
+}
+
+pub struct LineInfo {
+    pub display_type: DisplayType,
+    pub from: (f64, f64),
+    pub to: (f64, f64),
 }
 
 pub trait Drawer {
     fn draw(&mut self, display_info: DisplayInfo, width: u32, height: u32) -> Result<(), String>;
+
+    fn draw_line(&mut self, line_info: LineInfo, width: u32, height: u32) -> Result<(), String>;
 }
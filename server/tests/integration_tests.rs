@@ -1,7 +1,28 @@
-use server;
-
 #[test]
 fn non_existing_path() {
-    let result = server::run("non existing file", "0.0.0.0:8888");
+    let result = server::run(&server::Config {
+        paths: vec![String::from("non existing file")],
+        ..Default::default()
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn non_existing_checkpoint() {
+    let result = server::run(&server::Config {
+        resume: Some(String::from("non existing checkpoint")),
+        ..Default::default()
+    });
     assert!(result.is_err());
 }
+
+#[test]
+fn non_positive_checkpoint_seconds() {
+    for checkpoint_seconds in [0., -1., f64::NAN] {
+        let result = server::run(&server::Config {
+            checkpoint_seconds,
+            ..Default::default()
+        });
+        assert!(result.is_err());
+    }
+}